@@ -0,0 +1,51 @@
+//! Traits that the derives of [`derive_destructure`](https://docs.rs/derive_destructure)
+//! implement when asked to, so that generic code can use them:
+//! 
+//! ```ignore
+//! use derive_destructure_traits::Destructure;
+//! 
+//! fn consume<T: Destructure>(t: T) -> T::Fields {
+//!     t.destructure()
+//! }
+//! ```
+//! 
+//! The derives don't depend on this crate, so you need to add it to your own dependencies, and
+//! opt in with `#[destructure(impl_trait)]` or `#[remove_trait_impls(impl_trait)]`.
+//! 
+//! [`destructure_ok`] and [`destructure_some`] take apart the value inside a `Result` or an
+//! `Option`, so that teardown code doesn't have to unwrap it first:
+//! 
+//! ```ignore
+//! let (file, line) = destructure_ok(open_guard())?;
+//! ```
+
+#![no_std]
+
+/// Implemented by `#[derive(destructure)]` with `#[destructure(impl_trait)]`.
+pub trait Destructure {
+    /// The tuple of fields (or, for an enum, the `FooDestructured` enum) that `destructure()`
+    /// returns.
+    type Fields;
+
+    /// Takes the value apart without calling its `drop()` method.
+    fn destructure(self) -> Self::Fields;
+}
+
+/// Implemented by `#[derive(remove_trait_impls)]` with `#[remove_trait_impls(impl_trait)]`.
+pub trait RemoveTraitImpls {
+    /// The generated type without trait impls, like `FooWithoutTraitImpls`.
+    type Output;
+
+    /// Turns the value into the generated type without calling its `drop()` method.
+    fn remove_trait_impls(self) -> Self::Output;
+}
+
+/// Takes apart the value in `Ok`, and passes an `Err` through unchanged.
+pub fn destructure_ok<T: Destructure, E>(result: Result<T, E>) -> Result<T::Fields, E> {
+    result.map(T::destructure)
+}
+
+/// Takes apart the value in `Some`, and passes `None` through unchanged.
+pub fn destructure_some<T: Destructure>(option: Option<T>) -> Option<T::Fields> {
+    option.map(T::destructure)
+}
@@ -0,0 +1,814 @@
+//! Parsing of the helper attributes `#[destructure(...)]` and `#[remove_trait_impls(...)]`.
+
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Attribute, Expr, Lit, Meta, MetaList, MetaNameValue, Path, Token, Type};
+
+use crate::error::{ErrorContext, Errors};
+
+/// The options of `#[destructure(...)]` on fields, as opposed to on types.
+const FIELD_OPTIONS: [&str; 5] = ["unsafe_pin_ref", "copy", "skip", "forget", "flatten"];
+
+/// The traits that `#[remove_trait_impls(keep(...))]` can implement by delegating to the fields
+/// one by one.
+const KEPT_TRAITS: [&str; 4] = ["Debug", "PartialEq", "Eq", "Hash"];
+
+/// An item in the options of an attribute, or in a nested list like `keep(...)`. syn only parses
+/// such items as `Meta`, which can't be a literal like the field index in `redact(0)`.
+pub enum NestedMeta {
+    /// A plain name, like `patch` or `Debug`.
+    Word(Ident),
+    /// `name = value`, `name(...)`, or a path of more than one name.
+    Meta(Meta),
+    /// A literal, like `0`.
+    Lit(Lit),
+}
+
+impl Parse for NestedMeta {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Lit) {
+            return input.parse().map(NestedMeta::Lit);
+        }
+        match input.parse()? {
+            Meta::Path(ref path) if path.get_ident().is_some() => Ok(NestedMeta::Word(path.require_ident()?.clone())),
+            meta => Ok(NestedMeta::Meta(meta)),
+        }
+    }
+}
+
+impl ToTokens for NestedMeta {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match *self {
+            NestedMeta::Word(ref ident) => ident.to_tokens(tokens),
+            NestedMeta::Meta(ref meta) => meta.to_tokens(tokens),
+            NestedMeta::Lit(ref lit) => lit.to_tokens(tokens),
+        }
+    }
+}
+
+/// Parses the items of a list like `keep(Debug, Display)`.
+fn nested_list(cx: ErrorContext, list: &MetaList) -> syn::Result<Vec<NestedMeta>> {
+    match list.parse_args_with(Punctuated::<NestedMeta, Token![,]>::parse_terminated) {
+        Ok(nested) => Ok(nested.into_iter().collect()),
+        Err(err) => Err(cx.error(
+            format!("failed to parse the options of {}: {}", list.to_token_stream(), err),
+            &format!("separate the options with commas, like {}(a, b = \"c\")", path_name(&list.path)),
+        )),
+    }
+}
+
+/// The name of an option, for error messages.
+fn path_name(path: &Path) -> String {
+    path.to_token_stream().to_string().replace(' ', "")
+}
+
+/// Collects the items of all `#[name(...)]` attributes in `attrs`.
+fn nested_metas(cx: ErrorContext, attrs: &[Attribute], name: &str) -> syn::Result<Vec<NestedMeta>> {
+    let mut result = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident(name) {
+            continue;
+        }
+        match attr.meta {
+            Meta::List(ref list) => result.extend(nested_list(cx, list)?),
+            _ => return Err(cx.error(
+                format!("expected an attribute of the form #[{}(...)], found {}", name, attr.into_token_stream()),
+                &format!("put the options in parentheses, like #[{}(option)]", name),
+            )),
+        }
+    }
+    Ok(result)
+}
+
+/// Returns the literal of `key = value`, if the value is a literal.
+fn lit(meta: &MetaNameValue) -> Option<&Lit> {
+    match meta.value {
+        Expr::Lit(ref expr) => Some(&expr.lit),
+        _ => None,
+    }
+}
+
+/// Returns the string value of `key = "value"`.
+fn lit_str(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<String> {
+    match lit(meta) {
+        Some(Lit::Str(s)) => Ok(s.value()),
+        _ => Err(cx.error(
+            format!("expected a string literal for `{}`, found {}", path_name(&meta.path), meta.value.to_token_stream()),
+            &format!("put the value in quotes, like `{} = \"{}\"`", path_name(&meta.path), meta.value.to_token_stream()),
+        )),
+    }
+}
+
+/// Returns the integer value of `key = 12`.
+fn lit_usize(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<usize> {
+    match lit(meta).and_then(|lit| match *lit {
+        Lit::Int(ref i) => i.base10_parse().ok(),
+        _ => None,
+    }) {
+        Some(value) => Ok(value),
+        None => Err(cx.error(
+            format!("expected an integer for `{}`, found {}", path_name(&meta.path), meta.value.to_token_stream()),
+            &format!("write a number without quotes, like `{} = 12`", path_name(&meta.path)),
+        )),
+    }
+}
+
+/// Parses the string value of `key = "value"` as a path, a type, etc.
+fn lit_parse<T: Parse>(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<T> {
+    let value = lit_str(cx, meta)?;
+    syn::parse_str(&value).map_err(|err| {
+        cx.error(
+            format!("failed to parse `{}` for `{}`: {}", value, path_name(&meta.path), err),
+            &format!("write a path or type in the string, like `{} = \"crate::module::Name\"`", path_name(&meta.path)),
+        )
+    })
+}
+
+/// Returns the traits listed in `derive(...)`.
+fn derive_paths(cx: ErrorContext, derive: &MetaList) -> syn::Result<Vec<Path>> {
+    nested_list(cx, derive)?.iter().map(|trait_| {
+        match *trait_ {
+            NestedMeta::Word(ref ident) => Ok(Path::from(ident.clone())),
+            _ => Err(cx.error(
+                format!("expected a trait name in derive(...), found {}", trait_.into_token_stream()),
+                "list the traits by name, like derive(Clone, Debug)",
+            )),
+        }
+    }).collect()
+}
+
+/// Returns the traits listed in `keep_derives(except(...))`, which aren't derived again.
+fn keep_derives_except(cx: ErrorContext, keep_derives: &MetaList) -> syn::Result<Vec<Ident>> {
+    let mut except = Vec::new();
+    for item in &nested_list(cx, keep_derives)? {
+        match *item {
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("except") => {
+                except.extend(derive_paths(cx, list)?.into_iter().filter_map(|path| path.segments.into_iter().last().map(|segment| segment.ident)));
+            }
+            _ => return Err(cx.error(
+                format!("unknown keep_derives(...) option: {}", item.into_token_stream()),
+                "list the derives to leave out, like keep_derives(except(Serialize))",
+            )),
+        }
+    }
+    Ok(except)
+}
+
+/// Returns the identifier in `key = "ident"`.
+fn lit_ident(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<Ident> {
+    let value = lit_str(cx, meta)?;
+    syn::parse_str(&value).map_err(|_| {
+        cx.error(
+            format!("`{}` is not a valid identifier for `{}`", value, path_name(&meta.path)),
+            &format!("use a plain name, like `{} = \"some_name\"`", path_name(&meta.path)),
+        )
+    })
+}
+
+/// Returns the path set with `crate_path = "..."` in `#[destructure(...)]` or
+/// `#[remove_trait_impls(...)]`, if any. It applies to the code of both derives.
+pub fn crate_path(cx: ErrorContext, attrs: &[Attribute]) -> syn::Result<Option<Path>> {
+    let mut nested = nested_metas(cx, attrs, "destructure")?;
+    nested.extend(nested_metas(cx, attrs, "remove_trait_impls")?);
+    let mut path = None;
+    for nested in &nested {
+        if let NestedMeta::Meta(Meta::NameValue(ref meta)) = *nested {
+            if meta.path.is_ident("crate_path") {
+                path = Some(lit_parse(cx, meta)?);
+            }
+        }
+    }
+    Ok(path)
+}
+
+/// Returns whether `no_std` is set in `#[destructure(...)]` or `#[remove_trait_impls(...)]`, so
+/// that the generated code takes `Box`, `Cow` and `ToOwned` from `::alloc`. Like `crate_path`,
+/// it applies to the code of both derives.
+pub fn no_std(cx: ErrorContext, attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut nested = nested_metas(cx, attrs, "destructure")?;
+    nested.extend(nested_metas(cx, attrs, "remove_trait_impls")?);
+    Ok(nested.iter().any(|nested| match *nested {
+        NestedMeta::Word(ref ident) => ident == "no_std",
+        _ => false,
+    }))
+}
+
+/// Checks `msrv = "1.x"`, the oldest compiler version that the generated code has to support.
+/// The derive itself needs Rust 1.71, so older versions are rejected. Everything that's
+/// generated compiles on 1.71, so newer versions don't change the output.
+fn check_msrv(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<()> {
+    let value = lit_str(cx, meta)?;
+    let mut parts = value.split('.');
+    let major = parts.next().and_then(|part| part.parse::<u64>().ok());
+    let minor = parts.next().and_then(|part| part.parse::<u64>().ok());
+    match (major, minor) {
+        (Some(1), Some(minor)) if minor < 71 => Err(cx.error(
+            format!("`msrv = \"{}\"` is older than Rust 1.71, which derive_destructure itself requires. The generated code is compiled by the same compiler as the derive, so it never has to support an older version.", value),
+            "raise it to `msrv = \"1.71\"`, or remove msrv",
+        )),
+        (Some(major), Some(_)) if major >= 1 => Ok(()),
+        _ => Err(cx.error(
+            format!("expected a Rust version like \"1.56\" for `msrv`, found \"{}\"", value),
+            "write the version as major.minor, like `msrv = \"1.56\"`",
+        )),
+    }
+}
+
+/// How the generated methods are inlined, set with `inline = "never"`, `"hint"` or `"always"`.
+/// By default, they're `#[inline(always)]`, because they compile down to a few moves.
+#[derive(Clone, Copy, Default)]
+pub enum Inline {
+    Never,
+    Hint,
+    #[default]
+    Always,
+}
+
+impl Inline {
+    fn parse(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<Self> {
+        match &*lit_str(cx, meta)? {
+            "never" => Ok(Inline::Never),
+            "hint" => Ok(Inline::Hint),
+            "always" => Ok(Inline::Always),
+            value => Err(cx.error(
+                format!("expected \"never\", \"hint\" or \"always\" for `inline`, found \"{}\"", value),
+                "write one of `inline = \"never\"`, `inline = \"hint\"` or `inline = \"always\"`",
+            )),
+        }
+    }
+}
+
+impl ToTokens for Inline {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match *self {
+            Inline::Never => quote!(#[inline(never)]),
+            Inline::Hint => quote!(#[inline]),
+            Inline::Always => quote!(#[inline(always)]),
+        });
+    }
+}
+
+/// Where the events of `#[destructure(instrument = "...")]` go, set with `"log"` or `"tracing"`.
+#[derive(Clone, Copy)]
+pub enum Instrument {
+    Log,
+    Tracing,
+}
+
+impl Instrument {
+    fn parse(cx: ErrorContext, meta: &MetaNameValue) -> syn::Result<Self> {
+        match &*lit_str(cx, meta)? {
+            "log" => Ok(Instrument::Log),
+            "tracing" => Ok(Instrument::Tracing),
+            value => Err(cx.error(
+                format!("expected \"log\" or \"tracing\" for `instrument`, found \"{}\"", value),
+                "write `instrument = \"log\"` or `instrument = \"tracing\"`",
+            )),
+        }
+    }
+}
+
+/// Options set with `#[destructure(...)]` on a type that derives `destructure`.
+#[derive(Default)]
+pub struct DestructureOpts {
+    /// Generate a `FooPatch` type and an `apply()` method.
+    pub patch: bool,
+    /// Name the generated method `into_parts()` instead of `destructure()`.
+    pub into_parts: bool,
+    /// Name the generated method `defuse()`, and return a lone field by itself.
+    pub defuse: bool,
+    /// A custom name for the generated method.
+    pub rename: Option<Ident>,
+    /// Additional names for the generated method.
+    pub aliases: Vec<Ident>,
+    /// Generate `into_inner()`, which returns the only field.
+    pub into_inner: bool,
+    /// Generate the `FIELD_SIZES` and `FIELD_ALIGNS` constants.
+    pub field_layout: bool,
+    /// Generate the `FIELD_COUNT`, `FIELD_NAMES` and `NEEDS_DROP` constants.
+    pub field_info: bool,
+    /// Generate a `destructure_with_shell()` method that also returns the struct's storage.
+    pub with_shell: bool,
+    /// Generate a `foo_fields!` macro, possibly with a custom name.
+    pub fields_macro: Option<Option<Ident>>,
+    /// Generate the method in a `FooDestructureExt` trait instead of an inherent impl.
+    pub ext_trait: bool,
+    /// Generate `into_field_by_name()`.
+    pub field_by_name: bool,
+    /// Generate a `FooFields` type alias for the returned tuple, possibly with a custom name.
+    pub fields_type: Option<Option<Ident>>,
+    /// Generate `fields_any_mut()`.
+    pub fields_any_mut: bool,
+    /// Generate `drop_original_in_place()`.
+    pub drop_in_place: bool,
+    /// Generate a `FooPartsTuple` wrapper with a `Debug` impl that shows the field names.
+    pub parts_tuple: bool,
+    /// Why it's fine to bypass `Drop` for this type.
+    pub justification: Option<String>,
+    /// Generate `parts_layout()`.
+    pub parts_layout: bool,
+    /// Generate `try_into_<variant>()` for every variant of an enum.
+    pub try_into_variants: bool,
+    /// Generate a `FooParts` struct and a `destructure_named()` method that returns it.
+    pub named: bool,
+    /// Return `FooParts` from `destructure()` if there are more fields than this.
+    pub threshold: Option<usize>,
+    /// An existing struct to return from `destructure()` instead of a tuple.
+    pub into_struct: Option<Path>,
+    /// Generate `from_parts()`, the inverse of the generated method.
+    pub from_parts: bool,
+    /// Implement the `Destructure` trait of `derive_destructure_traits`.
+    pub impl_trait: bool,
+    /// Implement `From<Foo>` for the returned tuple.
+    pub into_tuple: bool,
+    /// Make the generated method a `const fn`.
+    pub const_fn: bool,
+    /// Declare the generated methods that bypass `Drop` as `unsafe fn`.
+    pub unsafe_fn: bool,
+    /// How the generated methods are inlined.
+    pub inline: Inline,
+    /// Generate `destructure_boxed(self: Box<Self>)`.
+    pub boxed: bool,
+    /// Generate `as_parts()`, which returns references to the fields.
+    pub as_parts: bool,
+    /// Generate `as_parts_mut()`, which returns mutable references to the fields.
+    pub as_parts_mut: bool,
+    /// Generate `destructure_cloned()`, which returns clones of the fields.
+    pub cloned: bool,
+    /// Generate `take_parts()`, which replaces the fields with their defaults.
+    pub take_parts: bool,
+    /// Generate a `take_<field>()` method and a `FooWithout<Field>` struct for every field.
+    pub rest: bool,
+    /// Generate `with_fields()`, which passes the fields to a closure.
+    pub with_fields: bool,
+    /// A function to call with `&mut self` before the struct is taken apart.
+    pub before: Option<Path>,
+    /// Generate a `with_<field>()` method for every field.
+    pub with_methods: bool,
+    /// Overwrite the struct's storage with zeros after its fields are moved out.
+    pub zeroize: bool,
+    /// Log every call of a generated method that bypasses `Drop`, with `log` or `tracing`.
+    pub instrument: Option<Instrument>,
+    /// Don't check that the type implements `Drop`.
+    pub allow_no_drop: bool,
+    /// Generate a `FooGuard` wrapper that can be taken apart or dropped later.
+    pub guard: bool,
+    /// Generate a `replace_<field>()` method for every field.
+    pub replace_methods: bool,
+}
+
+impl DestructureOpts {
+    pub fn from_attrs(cx: ErrorContext, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut opts = DestructureOpts::default();
+        let mut errors = Errors::default();
+        for nested in nested_metas(cx, attrs, "destructure")? {
+            errors.check(opts.parse_option(cx, &nested));
+        }
+        errors.finish()?;
+        Ok(opts)
+    }
+
+    /// Sets the option `nested` of `#[destructure(...)]`.
+    fn parse_option(&mut self, cx: ErrorContext, nested: &NestedMeta) -> syn::Result<()> {
+        match *nested {
+            NestedMeta::Word(ref ident) if ident == "patch" => self.patch = true,
+            NestedMeta::Word(ref ident) if ident == "into_parts" => self.into_parts = true,
+            NestedMeta::Word(ref ident) if ident == "defuse" => self.defuse = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("rename") => self.rename = Some(lit_ident(cx, meta)?),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("alias") => self.aliases.push(lit_ident(cx, meta)?),
+            NestedMeta::Word(ref ident) if ident == "into_inner" => self.into_inner = true,
+            NestedMeta::Word(ref ident) if ident == "field_layout" => self.field_layout = true,
+            NestedMeta::Word(ref ident) if ident == "field_info" => self.field_info = true,
+            NestedMeta::Word(ref ident) if ident == "with_shell" => self.with_shell = true,
+            NestedMeta::Word(ref ident) if ident == "boxed" => self.boxed = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("msrv") => check_msrv(cx, meta)?,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("inline") => self.inline = Inline::parse(cx, meta)?,
+            // Read by `crate_path()` and `no_std()`.
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("crate_path") => {}
+            NestedMeta::Word(ref ident) if ident == "no_std" => {}
+            NestedMeta::Word(ref ident) if ident == "fields_macro" => self.fields_macro = Some(None),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("fields_macro") => self.fields_macro = Some(Some(lit_ident(cx, meta)?)),
+            NestedMeta::Word(ref ident) if ident == "ext_trait" => self.ext_trait = true,
+            NestedMeta::Word(ref ident) if ident == "field_by_name" => self.field_by_name = true,
+            NestedMeta::Word(ref ident) if ident == "fields_type" => self.fields_type = Some(None),
+            NestedMeta::Word(ref ident) if ident == "fields_any_mut" => self.fields_any_mut = true,
+            NestedMeta::Word(ref ident) if ident == "as_parts" => self.as_parts = true,
+            NestedMeta::Word(ref ident) if ident == "as_parts_mut" => self.as_parts_mut = true,
+            NestedMeta::Word(ref ident) if ident == "cloned" => self.cloned = true,
+            NestedMeta::Word(ref ident) if ident == "take_parts" => self.take_parts = true,
+            NestedMeta::Word(ref ident) if ident == "rest" => self.rest = true,
+            NestedMeta::Word(ref ident) if ident == "with_fields" => self.with_fields = true,
+            NestedMeta::Word(ref ident) if ident == "with_methods" => self.with_methods = true,
+            NestedMeta::Word(ref ident) if ident == "zeroize" => self.zeroize = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("instrument") => self.instrument = Some(Instrument::parse(cx, meta)?),
+            NestedMeta::Word(ref ident) if ident == "allow_no_drop" => self.allow_no_drop = true,
+            NestedMeta::Word(ref ident) if ident == "guard" => self.guard = true,
+            NestedMeta::Word(ref ident) if ident == "replace_methods" => self.replace_methods = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("before") => self.before = Some(lit_parse(cx, meta)?),
+            NestedMeta::Word(ref ident) if ident == "drop_in_place" => self.drop_in_place = true,
+            NestedMeta::Word(ref ident) if ident == "parts_tuple" => self.parts_tuple = true,
+            NestedMeta::Word(ref ident) if ident == "parts_layout" => self.parts_layout = true,
+            NestedMeta::Word(ref ident) if ident == "try_into_variants" => self.try_into_variants = true,
+            NestedMeta::Word(ref ident) if ident == "named" => self.named = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("threshold") => self.threshold = Some(lit_usize(cx, meta)?),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("into_struct") => self.into_struct = Some(lit_parse(cx, meta)?),
+            NestedMeta::Word(ref ident) if ident == "from_parts" => self.from_parts = true,
+            NestedMeta::Word(ref ident) if ident == "into_tuple" => self.into_tuple = true,
+            NestedMeta::Word(ref ident) if ident == "impl_trait" => self.impl_trait = true,
+            NestedMeta::Word(ref ident) if ident == "const_fn" => self.const_fn = true,
+            NestedMeta::Word(ref ident) if ident == "unsafe_fn" => self.unsafe_fn = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("justification") => self.justification = Some(lit_str(cx, meta)?),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("fields_type") => self.fields_type = Some(Some(lit_ident(cx, meta)?)),
+            NestedMeta::Word(ref ident) if FIELD_OPTIONS.iter().any(|option| ident == option) => return Err(cx.error(
+                format!("#[destructure({})] is an option for fields, not for types", ident),
+                &format!("put #[destructure({})] on the field instead", ident),
+            )),
+            _ => return Err(cx.error(
+                format!("unknown #[destructure] option: {}", nested.into_token_stream()),
+                "remove it, or check the spelling against the options in the documentation of derive_destructure",
+            )),
+        }
+        Ok(())
+    }
+
+    /// The first option that was set that only works on structs, if any.
+    pub fn struct_only_option(&self) -> Option<&'static str> {
+        let options = [
+            (self.patch, "patch"),
+            (self.into_inner, "into_inner"),
+            (self.into_tuple, "into_tuple"),
+            (self.field_layout, "field_layout"),
+            (self.field_info, "field_info"),
+            (self.with_shell, "with_shell"),
+            (self.boxed, "boxed"),
+            (self.fields_macro.is_some(), "fields_macro"),
+            (self.ext_trait, "ext_trait"),
+            (self.field_by_name, "field_by_name"),
+            (self.fields_type.is_some(), "fields_type"),
+            (self.fields_any_mut, "fields_any_mut"),
+            (self.as_parts, "as_parts"),
+            (self.as_parts_mut, "as_parts_mut"),
+            (self.cloned, "cloned"),
+            (self.take_parts, "take_parts"),
+            (self.parts_tuple, "parts_tuple"),
+            (self.parts_layout, "parts_layout"),
+            (self.with_fields, "with_fields"),
+            (self.before.is_some(), "before"),
+            (self.with_methods, "with_methods"),
+            (self.zeroize, "zeroize"),
+            (self.instrument.is_some(), "instrument"),
+            (self.guard, "guard"),
+            (self.threshold.is_some(), "threshold"),
+            (self.into_struct.is_some(), "into_struct"),
+            (self.replace_methods, "replace_methods"),
+            (self.from_parts, "from_parts"),
+            (self.const_fn, "const_fn"),
+        ];
+        options.iter().find(|&&(is_set, _)| is_set).map(|&(_, name)| name)
+    }
+
+    /// The first option that was set that borrows the fields, if any. Those don't work on
+    /// `#[repr(packed)]` structs.
+    pub fn borrowing_option(&self) -> Option<&'static str> {
+        let options = [
+            (self.fields_any_mut, "fields_any_mut"),
+            (self.as_parts, "as_parts"),
+            (self.as_parts_mut, "as_parts_mut"),
+            (self.cloned, "cloned"),
+            (self.take_parts, "take_parts"),
+            (self.replace_methods, "replace_methods"),
+        ];
+        options.iter().find(|&&(is_set, _)| is_set).map(|&(_, name)| name)
+    }
+
+    /// Whether `destructure()` returns `FooParts` instead of a tuple of `field_count` fields.
+    pub fn returns_parts_struct(&self, field_count: usize) -> bool {
+        self.threshold.is_some_and(|threshold| field_count > threshold)
+    }
+
+    /// The first option that was set that describes or returns the fields with their own types,
+    /// if any. Those can't be combined with `#[destructure(flatten)]` fields.
+    pub fn unflattened_option(&self) -> Option<&'static str> {
+        let options = [
+            (self.field_layout, "field_layout"),
+            (self.field_info, "field_info"),
+            (self.parts_layout, "parts_layout"),
+            (self.parts_tuple, "parts_tuple"),
+            (self.fields_macro.is_some(), "fields_macro"),
+            (self.fields_type.is_some(), "fields_type"),
+            (self.field_by_name, "field_by_name"),
+            (self.with_shell, "with_shell"),
+            (self.boxed, "boxed"),
+            (self.cloned, "cloned"),
+            (self.take_parts, "take_parts"),
+            (self.with_fields, "with_fields"),
+            (self.from_parts, "from_parts"),
+            (self.const_fn, "const_fn"),
+        ];
+        options.iter().find(|&&(is_set, _)| is_set).map(|&(_, name)| name)
+    }
+
+    /// The name of the generated method.
+    pub fn method_name(&self) -> Ident {
+        if let Some(ref rename) = self.rename {
+            return rename.clone();
+        }
+        let name = if self.defuse {
+            "defuse"
+        } else if self.into_parts {
+            "into_parts"
+        } else {
+            "destructure"
+        };
+        Ident::new(name, Span::call_site())
+    }
+}
+
+/// Options set with `#[destructure(...)]` on a field of a type that derives `destructure`.
+#[derive(Default)]
+pub struct DestructureFieldOpts {
+    /// Allow destructuring a field of type `Pin<&T>` or `Pin<&mut T>`.
+    pub unsafe_pin_ref: bool,
+    /// The field's type is `Copy`, so it can be copied out without `unsafe`.
+    pub copy: bool,
+    /// Drop the field in `destructure()` instead of returning it.
+    pub skip: bool,
+    /// Leak the field in `destructure()` instead of returning it.
+    pub forget: bool,
+    /// Return the fields of the field, with the `Destructure` trait, instead of the field itself.
+    pub flatten: bool,
+}
+
+impl DestructureFieldOpts {
+    pub fn from_attrs(cx: ErrorContext, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut opts = DestructureFieldOpts::default();
+        let mut errors = Errors::default();
+        for nested in nested_metas(cx, attrs, "destructure")? {
+            match nested {
+                NestedMeta::Word(ref ident) if ident == "unsafe_pin_ref" => opts.unsafe_pin_ref = true,
+                NestedMeta::Word(ref ident) if ident == "copy" => opts.copy = true,
+                NestedMeta::Word(ref ident) if ident == "skip" => opts.skip = true,
+                NestedMeta::Word(ref ident) if ident == "forget" => opts.forget = true,
+                NestedMeta::Word(ref ident) if ident == "flatten" => opts.flatten = true,
+                _ => errors.push(cx.error(
+                    format!("unknown #[destructure] field option: {}", nested.into_token_stream()),
+                    "the options on fields are #[destructure(unsafe_pin_ref)], #[destructure(copy)], #[destructure(skip)], #[destructure(forget)] and #[destructure(flatten)]",
+                )),
+            }
+        }
+        if opts.skip && opts.forget {
+            errors.push(cx.error(
+                "a field can't be both #[destructure(skip)] and #[destructure(forget)].".to_owned(),
+                "use #[destructure(skip)] to drop the field, or #[destructure(forget)] to leak it",
+            ));
+        }
+        if opts.flatten && (opts.skip || opts.forget) {
+            errors.push(cx.error(
+                "a field can't be both #[destructure(flatten)] and #[destructure(skip)] or #[destructure(forget)], because it's not returned.".to_owned(),
+                "remove #[destructure(flatten)] from the field",
+            ));
+        }
+        errors.finish()?;
+        Ok(opts)
+    }
+}
+
+/// Options set with `#[remove_trait_impls(...)]` on a type that derives `remove_trait_impls`.
+#[derive(Default)]
+pub struct RemoveTraitImplsOpts {
+    /// A custom name for the generated type.
+    pub name: Option<Ident>,
+    /// A custom suffix for the name of the generated type, instead of `WithoutTraitImpls`.
+    pub suffix: Option<String>,
+    /// Unsafe marker traits (like `Send` and `Sync`) to implement for the generated type.
+    pub unsafe_impls: Vec<Ident>,
+    /// Implement `Debug` for the generated type, printing `***` for the listed fields.
+    pub debug: Option<Vec<String>>,
+    /// Check at compile time that the generated type has the same size and alignment.
+    pub assert_layout: bool,
+    /// Generate `try_restore()` on the generated type, with a validator and its error type.
+    pub try_restore: Option<(Path, Type)>,
+    /// Implement `From<FooWithoutTraitImpls>` for the original type.
+    pub reverse_from: bool,
+    /// Implement the `RemoveTraitImpls` trait of `derive_destructure_traits`.
+    pub impl_trait: bool,
+    /// Traits to derive for the generated type.
+    pub derives: Vec<Path>,
+    /// Derive the same traits as the original type, except the listed ones.
+    pub keep_derives: Option<Vec<Ident>>,
+    /// Formatting traits to implement for the generated type by delegating to the original type.
+    pub keep: Vec<Ident>,
+    /// Attributes of fields and variants to copy onto the generated type, like `serde`.
+    pub forward_field_attrs: Vec<Ident>,
+    /// Turn borrowed fields (`&T` and `Cow<T>`) into owned ones in the generated type.
+    pub owned: bool,
+    /// Additional generated types, like `FooNoDrop`, and what they keep.
+    pub variants: Vec<(Ident, VariantOpts)>,
+    /// Generate a `#[repr(C)]` tagged union instead of an enum.
+    pub repr_c_tagged: bool,
+    /// Why it's fine to bypass `Drop` for this type.
+    pub justification: Option<String>,
+    /// How the generated methods are inlined.
+    pub inline: Inline,
+    /// A module to generate the types in, instead of next to the original type.
+    pub module: Option<Ident>,
+}
+
+/// Options for one of the additional types in `#[remove_trait_impls(variants(...))]`.
+#[derive(Default)]
+pub struct VariantOpts {
+    /// Derive the same traits as the original type, except the listed ones.
+    pub keep_derives: Option<Vec<Ident>>,
+    /// Traits to derive for this type.
+    pub derives: Vec<Path>,
+}
+
+impl VariantOpts {
+    fn parse(cx: ErrorContext, nested: &NestedMeta) -> syn::Result<(Ident, Self)> {
+        let mut opts = VariantOpts::default();
+        match *nested {
+            NestedMeta::Word(ref ident) => Ok((ident.clone(), opts)),
+            NestedMeta::Meta(Meta::List(ref list)) => {
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Word(ref ident) if ident == "keep_derives" => opts.keep_derives = Some(Vec::new()),
+                        NestedMeta::Meta(Meta::List(ref keep)) if keep.path.is_ident("keep_derives") => opts.keep_derives = Some(keep_derives_except(cx, keep)?),
+                        NestedMeta::Meta(Meta::List(ref derive)) if derive.path.is_ident("derive") => opts.derives.extend(derive_paths(cx, derive)?),
+                        _ => return Err(cx.error(
+                            format!("unknown option for {}(...): {}", path_name(&list.path), item.into_token_stream()),
+                            &format!("use {}(keep_derives) or {}(derive(...))", path_name(&list.path), path_name(&list.path)),
+                        )),
+                    }
+                }
+                match list.path.get_ident() {
+                    Some(ident) => Ok((ident.clone(), opts)),
+                    None => Err(cx.error(
+                        format!("expected a type suffix like NoDrop(...) in variants(...), found {}", nested.into_token_stream()),
+                        "write variants(NoDrop(keep_derives), Bare)",
+                    )),
+                }
+            }
+            _ => Err(cx.error(
+                format!("expected a type suffix like NoDrop(...) in variants(...), found {}", nested.into_token_stream()),
+                "write variants(NoDrop(keep_derives), Bare)",
+            )),
+        }
+    }
+}
+
+impl RemoveTraitImplsOpts {
+    pub fn from_attrs(cx: ErrorContext, attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut opts = RemoveTraitImplsOpts::default();
+        let mut errors = Errors::default();
+        for nested in nested_metas(cx, attrs, "remove_trait_impls")? {
+            errors.check(opts.parse_option(cx, &nested));
+        }
+        if opts.name.is_some() && opts.suffix.is_some() {
+            errors.push(cx.error(
+                "#[remove_trait_impls] can't have both `name` and `suffix`.".to_owned(),
+                "keep only one of them",
+            ));
+        }
+        errors.finish()?;
+        Ok(opts)
+    }
+
+    /// Sets the option `nested` of `#[remove_trait_impls(...)]`.
+    fn parse_option(&mut self, cx: ErrorContext, nested: &NestedMeta) -> syn::Result<()> {
+        match *nested {
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("unsafe_impl") => {
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Word(ref ident) => self.unsafe_impls.push(ident.clone()),
+                        _ => return Err(cx.error(
+                            format!("expected a trait name in unsafe_impl(...), found {}", item.into_token_stream()),
+                            "list the traits by name, like unsafe_impl(Send, Sync)",
+                        )),
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("msrv") => check_msrv(cx, meta)?,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("inline") => self.inline = Inline::parse(cx, meta)?,
+            // Read by `crate_path()` and `no_std()`.
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("crate_path") => {}
+            NestedMeta::Word(ref ident) if ident == "no_std" => {}
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("name") => self.name = Some(lit_ident(cx, meta)?),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("suffix") => self.suffix = Some(lit_str(cx, meta)?),
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("module") => self.module = Some(lit_ident(cx, meta)?),
+            NestedMeta::Word(ref ident) if ident == "debug" => self.debug = Some(Vec::new()),
+            NestedMeta::Word(ref ident) if ident == "assert_layout" => self.assert_layout = true,
+            NestedMeta::Word(ref ident) if ident == "owned" => self.owned = true,
+            NestedMeta::Word(ref ident) if ident == "reverse_from" => self.reverse_from = true,
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("derive") => self.derives.extend(derive_paths(cx, list)?),
+            NestedMeta::Word(ref ident) if ident == "keep_derives" => self.keep_derives = Some(Vec::new()),
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("keep_derives") => self.keep_derives = Some(keep_derives_except(cx, list)?),
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("keep") => {
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Word(ref ident) if KEPT_TRAITS.iter().any(|trait_| ident == trait_) => self.keep.push(ident.clone()),
+                        _ => return Err(cx.error(
+                            format!("keep(...) can't implement {}, because only {} can be implemented by delegating to the fields one by one.", item.into_token_stream(), KEPT_TRAITS.join(", ")),
+                            "remove it from keep(...), and implement it for the generated type by hand",
+                        )),
+                    }
+                }
+            }
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("forward_field_attrs") => {
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Word(ref ident) => self.forward_field_attrs.push(ident.clone()),
+                        _ => return Err(cx.error(
+                            format!("expected an attribute name in forward_field_attrs(...), found {}", item.into_token_stream()),
+                            "list the attributes by name, like forward_field_attrs(serde)",
+                        )),
+                    }
+                }
+            }
+            NestedMeta::Word(ref ident) if ident == "impl_trait" => self.impl_trait = true,
+            NestedMeta::Word(ref ident) if ident == "repr_c_tagged" => self.repr_c_tagged = true,
+            NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("justification") => self.justification = Some(lit_str(cx, meta)?),
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("variants") => {
+                for nested in &nested_list(cx, list)? {
+                    self.variants.push(VariantOpts::parse(cx, nested)?);
+                }
+            }
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("try_restore") => {
+                let mut validate = None;
+                let mut error = None;
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("validate") => validate = Some(lit_parse(cx, meta)?),
+                        NestedMeta::Meta(Meta::NameValue(ref meta)) if meta.path.is_ident("error") => error = Some(lit_parse(cx, meta)?),
+                        _ => return Err(cx.error(
+                            format!("unknown try_restore(...) option: {}", item.into_token_stream()),
+                            "write try_restore(validate = \"path::to::function\", error = \"ErrorType\")",
+                        )),
+                    }
+                }
+                match (validate, error) {
+                    (Some(validate), Some(error)) => self.try_restore = Some((validate, error)),
+                    _ => return Err(cx.error(
+                        "try_restore(...) needs both `validate = \"...\"` and `error = \"...\"`".to_owned(),
+                        "write try_restore(validate = \"path::to::function\", error = \"ErrorType\")",
+                    )),
+                }
+            }
+            NestedMeta::Meta(Meta::List(ref list)) if list.path.is_ident("debug") => {
+                let mut redacted = Vec::new();
+                for item in &nested_list(cx, list)? {
+                    match *item {
+                        NestedMeta::Meta(Meta::List(ref redact)) if redact.path.is_ident("redact") => {
+                            for field in &nested_list(cx, redact)? {
+                                match *field {
+                                    NestedMeta::Word(ref ident) => redacted.push(ident.to_string()),
+                                    NestedMeta::Lit(Lit::Int(ref index)) => redacted.push(index.base10_digits().to_owned()),
+                                    _ => return Err(cx.error(
+                                        format!("expected a field name or index in redact(...), found {}", field.into_token_stream()),
+                                        "list the fields by name or index, like redact(password, 0)",
+                                    )),
+                                }
+                            }
+                        }
+                        _ => return Err(cx.error(
+                            format!("unknown debug(...) option: {}", item.into_token_stream()),
+                            "the only option is debug(redact(...))",
+                        )),
+                    }
+                }
+                self.debug = Some(redacted);
+            }
+            _ => return Err(cx.error(
+                format!("unknown #[remove_trait_impls] option: {}", nested.into_token_stream()),
+                "remove it, or check the spelling against the options in the documentation of derive_destructure",
+            )),
+        }
+        Ok(())
+    }
+
+    /// The visibility of the generated methods. They're private, unless they're generated in a
+    /// module, where they're `pub(super)` so that they can still be called next to the type.
+    pub fn method_vis(&self) -> TokenStream {
+        if self.module.is_some() {
+            quote!(pub(super))
+        } else {
+            TokenStream::new()
+        }
+    }
+
+    /// The name of the generated type for a type called `name`.
+    pub fn type_name(&self, cx: ErrorContext, name: &Ident) -> syn::Result<Ident> {
+        if let Some(ref type_name) = self.name {
+            return Ok(type_name.clone());
+        }
+        let suffix = self.suffix.as_ref().map_or("WithoutTraitImpls", |suffix| suffix);
+        let type_name = format!("{}{}", name, suffix);
+        syn::parse_str(&type_name).map_err(|_| {
+            cx.error(
+                format!("`{}` is not a valid name for the generated type", type_name),
+                "use a suffix that's valid in an identifier, like `suffix = \"Parts\"`",
+            )
+        })
+    }
+}
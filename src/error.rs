@@ -0,0 +1,98 @@
+//! Reporting errors in the input of the derives.
+//!
+//! Errors are `syn::Error`s that point at the type or field they're about. They're returned as a
+//! `syn::Result` up to the derive's entry point, which turns them into `compile_error!`s. Checks
+//! that don't depend on each other collect their errors in `Errors`, so that all of them are
+//! reported at once.
+
+use proc_macro2::{Ident, Span};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Field};
+
+/// What an error is about: the type that the derive is on, and possibly one of its fields.
+#[derive(Clone, Copy)]
+pub struct ErrorContext<'a> {
+    type_name: &'a Ident,
+    field: Option<&'a Field>,
+}
+
+impl<'a> ErrorContext<'a> {
+    pub fn new(input: &'a DeriveInput) -> Self {
+        ErrorContext {
+            type_name: &input.ident,
+            field: None,
+        }
+    }
+
+    pub fn with_field(self, field: &'a Field) -> Self {
+        ErrorContext {
+            field: Some(field),
+            ..self
+        }
+    }
+
+    /// Creates an error at the type (or field). With the `verbose-errors` feature, the message
+    /// also says which type (and field) it's about, and suggests `fix`.
+    pub fn error(self, message: String, fix: &str) -> syn::Error {
+        let message = if cfg!(feature = "verbose-errors") {
+            let location = match self.field {
+                Some(field) => {
+                    let ty = &field.ty;
+                    match field.ident {
+                        Some(ref ident) => format!("field `{}: {}` of `{}`", ident, quote!(#ty), self.type_name),
+                        None => format!("tuple field `{}` of `{}`", quote!(#ty), self.type_name),
+                    }
+                }
+                None => format!("`{}`", self.type_name),
+            };
+            format!("{}\n  in {}\n  help: {}", message, location, fix)
+        } else {
+            message
+        };
+        syn::Error::new(self.span(), message)
+    }
+
+    fn span(self) -> Span {
+        match self.field {
+            Some(field) => match field.ident {
+                Some(ref ident) => ident.span(),
+                None => field.ty.span(),
+            },
+            None => self.type_name.span(),
+        }
+    }
+}
+
+/// The errors of checks that don't depend on each other, combined into one with
+/// `syn::Error::combine`.
+#[derive(Default)]
+pub struct Errors(Option<syn::Error>);
+
+impl Errors {
+    pub fn push(&mut self, error: syn::Error) {
+        match self.0 {
+            Some(ref mut errors) => errors.combine(error),
+            None => self.0 = Some(error),
+        }
+    }
+
+    /// Adds the error of `result`, if it is one, and returns its value otherwise.
+    pub fn check<T>(&mut self, result: syn::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(error) => {
+                self.push(error);
+                None
+            }
+        }
+    }
+
+    /// Returns all the errors, if there were any.
+    pub fn finish(self) -> syn::Result<()> {
+        match self.0 {
+            Some(errors) => Err(errors),
+            None => Ok(()),
+        }
+    }
+}
@@ -12,6 +12,9 @@
 //! 
 //! Then you have 2 ways to use this crate:
 //! 
+//! (Both derives can also be written in PascalCase, as `#[derive(Destructure)]` and
+//! `#[derive(RemoveTraitImpls)]`, to match derives like `Debug` and `Clone`.)
+//! 
 //! # Option 1: `#[derive(destructure)]`
 //! 
 //! If you mark a struct with `#[derive(destructure)]`, then you can destructure it using
@@ -24,6 +27,22 @@
 //! 
 //! Note: in Rust, a tuple of 1 element is denoted as `(x,)`, not `(x)`.
 //! 
+//! `destructure()` and the other generated methods that take the struct apart are `#[must_use]`,
+//! and so is the type generated by `#[derive(remove_trait_impls)]`: ignoring the result drops the
+//! fields without the cleanup that `drop()` would have done, which is rarely what you want.
+//! 
+//! `#[derive(destructure)]` works on enums too. For an enum `Foo`, it generates an enum
+//! `FooDestructured` (with the same visibility as `Foo`) with the same variants, but with the
+//! fields of every variant as tuple fields, in order, and without `Drop`. Then
+//! `foo.destructure()` returns a `FooDestructured`:
+//! ```ignore
+//! match foo.destructure() {
+//!     FooDestructured::A(field_1, field_2) => ...,
+//!     FooDestructured::B => ...,
+//! }
+//! ```
+//! Most of the options below only work on structs.
+//! 
 //! # Option 2: `#[derive(remove_trait_impls)]`
 //! 
 //! If you mark your struct with `#[derive(remove_trait_impls)]`, then you can do
@@ -37,9 +56,29 @@
 //! 
 //! The name of the resulting struct is the original name plus the suffix `WithoutTraitImpls`.
 //! For example, `Foo` becomes `FooWithoutTraitImpls`. But you usually don't need to write
-//! out this name.
+//! out this name. It can be changed with `#[remove_trait_impls(name = "...")]`, or
+//! `#[remove_trait_impls(suffix = "...")]` to keep the original name with a different suffix.
+//! 
+//! The resulting struct has the same visibility as the original one, and so do its fields, so
+//! `pub struct Foo { pub a: A, b: B }` gives `pub struct FooWithoutTraitImpls { pub a: A, b: B }`.
+//! 
+//! The `#[repr(...)]` attributes of the original type, like `#[repr(C)]` or `#[repr(align(8))]`,
+//! are copied onto the generated type, so that it has the same layout for FFI.
 //! 
-//! `#[derive(remove_trait_impls)]` works on enums too.
+//! The doc comments of the original type, its fields and its variants are copied onto the
+//! generated type, so that it's documented too.
+//! 
+//! Fields and variants with `#[cfg(...)]` attributes only exist in the generated type if they
+//! exist in the original one. This isn't supported for fields of tuple structs and tuple variants,
+//! because it would change the indices of the other fields.
+//! 
+//! `#[derive(remove_trait_impls)]` works on enums too. Explicit discriminants (like `A = 1` or
+//! `B(u32) = 7`) are kept on the generated enum.
+//! 
+//! It also works on `#[repr(C)]` unions, whose fields are all `Copy` or wrapped in
+//! `ManuallyDrop`. The generated union has the same fields and `#[repr(...)]` attributes, and
+//! the whole union is copied into it, whichever field is active. The `owned`, `debug`,
+//! `try_restore` and `reverse_from` options below don't work on unions.
 //! 
 //! # Example:
 //! ```
@@ -79,182 +118,3203 @@
 //!     println!("{}", x.some_int);
 //! }
 //! ```
+//! 
+//! # `destructure_let!`
+//! 
+//! A tuple loses the names of the fields, so it's easy to mix up two fields of the same type.
+//! For structs with `#[destructure(named)]`, the `destructure_let!` macro binds the fields to
+//! variables by name instead:
+//! ```ignore
+//! destructure_let!(Foo { some_str, some_int: renamed } = foo);
+//! ```
+//! This takes `foo` apart with `destructure_named()`, without running its `drop()` method, and
+//! matches the result like a struct pattern. So it's a compile error if a field is missing or
+//! misspelled, and fields can be renamed or ignored with `_` like in any struct pattern.
+//! `FooParts` must be in scope wherever `Foo` is named this way.
+//! 
+//! To keep only some of the fields, name them in `destructure_some!` and end with `..`:
+//! ```ignore
+//! destructure_some!(foo => { some_str, .. });
+//! ```
+//! This binds `some_str` and drops the other fields normally, still without running the
+//! `drop()` method of `Foo` itself. Fields can be bound as `mut some_str` or to a pattern, like
+//! `some_str: renamed`.
+//! 
+//! # `destructure_match!`
+//! 
+//! For enums, `destructure_match!` matches on the value like `match`, but without running its
+//! `drop()` method, so the fields can be moved into the arms:
+//! ```ignore
+//! destructure_match!(packet {
+//!     Packet::Data(buf) => send(buf),
+//!     Packet::Eof => {}
+//! })
+//! ```
+//! It calls `destructure()` and rewrites the patterns to match `PacketDestructured` instead of
+//! `Packet`, so the variants must be named with their enum, like `Packet::Data`. Use
+//! `#[destructure(named)]` on the enum to match struct-like variants with their field names,
+//! like `Packet::Header { len, .. }`.
+//! 
+//! # Packed structs
+//! 
+//! Both derives work on `#[repr(packed)]` structs. Their fields may not be aligned, so they're
+//! read with `ptr::read_unaligned` through `ptr::addr_of!` instead of through a reference. The options that borrow the fields, `fields_any_mut`, `as_parts`,
+//! `as_parts_mut`, `cloned`, `take_parts`, `replace_methods` and `#[remove_trait_impls(debug)]`,
+//! don't work on them.
+//! 
+//! # Unsized fields
+//! 
+//! A struct whose last field is unsized can't be passed by value, so it can't be taken apart
+//! either. `#[derive(destructure)]` rejects fields whose type is written as a slice, `str` or a
+//! `dyn` trait object, with an error that points at the field:
+//! 
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! struct Bytes {
+//!     len: usize,
+//!     data: [u8],
+//! }
+//! # impl Drop for Bytes {
+//! #     fn drop(&mut self) {}
+//! # }
+//! # fn main() {}
+//! ```
+//! 
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! struct Text {
+//!     len: usize,
+//!     data: str,
+//! }
+//! # impl Drop for Text {
+//! #     fn drop(&mut self) {}
+//! # }
+//! # fn main() {}
+//! ```
+//! 
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! struct Callback {
+//!     len: usize,
+//!     data: dyn Fn(),
+//! }
+//! # impl Drop for Callback {
+//! #     fn drop(&mut self) {}
+//! # }
+//! # fn main() {}
+//! ```
+//! 
+//! The check only looks at how the type is written. A field of a generic type `T: ?Sized`, or
+//! of an alias for an unsized type, gets through it, and the error then points at the generated
+//! code instead.
+//! 
+//! # Options
+//! 
+//! The generated code can be customized with `#[destructure(...)]` and
+//! `#[remove_trait_impls(...)]` attributes on the type.
+//! 
+//! ## `#[destructure(patch)]`
+//! 
+//! Struct update syntax (`Foo { some_int: 5, ..foo }`) isn't allowed for types that implement
+//! `Drop`. With `#[destructure(patch)]`, a struct `FooPatch` is generated that has an `Option`
+//! for every field of `Foo`, along with a method `fn apply(self, patch: FooPatch) -> Foo`
+//! that replaces every field for which the patch contains `Some(...)`:
+//! ```ignore
+//! let foo = foo.apply(FooPatch { some_int: Some(5), ..Default::default() });
+//! ```
+//! The old values of the replaced fields are dropped, but `Foo`'s `drop()` method is not called.
+//! 
+//! ## `#[destructure(into_parts)]`
+//! 
+//! Names the generated method `into_parts()` instead of `destructure()`, following the
+//! convention of methods like `BufWriter::into_parts`.
+//! 
+//! ## `#[destructure(defuse)]`
+//! 
+//! A preset for guard types, whose `drop()` cancels or rolls something back unless the guard is
+//! defused first. Names the generated method `defuse()` instead of `destructure()`, like the
+//! methods of scopeguard-style APIs, and if only one field is returned, for example because the
+//! field holding the rollback is `#[destructure(skip)]`, `defuse()` returns that field by itself
+//! instead of a tuple of one element:
+//! 
+//! ```
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! #[destructure(defuse)]
+//! struct Rollback<T> {
+//!     payload: T,
+//!     #[destructure(skip)]
+//!     on_drop: fn(),
+//! }
+//! 
+//! impl<T> Drop for Rollback<T> {
+//!     fn drop(&mut self) {
+//!         (self.on_drop)()
+//!     }
+//! }
+//! 
+//! # fn main() {
+//! let guard = Rollback { payload: 42, on_drop: || panic!("rolled back") };
+//! let payload: i32 = guard.defuse();
+//! # assert_eq!(payload, 42);
+//! # }
+//! ```
+//! 
+//! `rename = "..."` takes precedence over the name `defuse()`.
+//! 
+//! ## `#[destructure(rename = "...")]`
+//! 
+//! Gives the generated method the given name instead of `destructure()`, for example when `Foo`
+//! already has a method called `destructure()`. Methods derived from its name, like
+//! `destructure_with_shell()`, are renamed along with it.
+//! 
+//! ## `#[destructure(try_into_variants)]`
+//! 
+//! Only for enums. Generates a method `fn try_into_a(self) -> Result<(A1, A2, ...), Foo>` for
+//! every variant `A` of `Foo`, which returns the fields of that variant as a tuple, without
+//! running `drop()`, or `self` unchanged if it's a different variant. The method name is
+//! `try_into_` followed by the variant name in snake case.
+//! 
+//! ## `#[destructure(alias = "...")]`
+//! 
+//! Generates an additional method with the given name that does the same as `destructure()`.
+//! For example, `#[destructure(into_parts, alias = "destructure")]` generates both
+//! `into_parts()` and `destructure()`.
+//! 
+//! ## `#[destructure(into_inner)]`
+//! 
+//! For a wrapper with exactly one field (not counting skipped ones), generates
+//! `fn into_inner(self) -> T`, which returns the field itself instead of a tuple of one element,
+//! like the `into_inner()` methods of the standard library.
+//! 
+//! ## `#[destructure(field_layout)]`
+//! 
+//! Generates the associated constants `FIELD_SIZES: &[usize]` and `FIELD_ALIGNS: &[usize]`,
+//! containing the size and alignment of every field, in the order in which `destructure()`
+//! returns them.
+//! 
+//! ## `#[destructure(field_info)]`
+//! 
+//! Generates the associated constants `FIELD_COUNT: usize`, `FIELD_NAMES: [&str; N]` and
+//! `NEEDS_DROP: [bool; N]`, describing the fields returned by `destructure()`, in order. The
+//! names of tuple struct fields are `"0"`, `"1"`, etc. Tests can use these to check that code
+//! which destructures `Foo` stays in sync with its definition.
+//! 
+//! ## `#[destructure(parts_layout)]`
+//! 
+//! Generates an associated function `const fn parts_layout() -> Layout` that returns the layout
+//! of the tuple returned by `destructure()`, for code that needs to reserve memory for it ahead
+//! of time.
+//! 
+//! ## `#[destructure(with_shell)]`
+//! 
+//! Generates a method `destructure_with_shell(self) -> ((field_1, field_2, ...), MaybeUninit<Foo>)`
+//! that also returns the storage that the struct occupied, so that it can be reused, for example
+//! by writing a new value into it. The fields have been moved out of the returned
+//! `MaybeUninit`, so it must be treated as uninitialized.
+//! 
+//! ## `#[destructure(inline = "...")]` and `#[remove_trait_impls(inline = "...")]`
+//! 
+//! The generated methods are `#[inline(always)]` by default, because they compile down to a few
+//! moves. For very large or very generic types, that can bloat the code. `inline = "hint"` makes
+//! them `#[inline]` instead, and `inline = "never"` makes them `#[inline(never)]`.
+//! 
+//! ## `#[destructure(boxed)]`
+//! 
+//! Generates a method `destructure_boxed(self: Box<Foo>) -> (field_1, field_2, ...)` for values
+//! that live on the heap. It moves the fields straight out of the box and frees it without
+//! running `drop()`, so a large struct is never moved onto the stack as a whole.
+//! 
+//! ## `#[destructure(msrv = "1.x")]` and `#[remove_trait_impls(msrv = "1.x")]`
+//! 
+//! Makes the generated code compile on the given version of Rust and newer. By default, the
+//! generated code may use anything that's stable in the compiler that builds it.
+//! 
+//! derive_destructure itself requires Rust 1.71 or newer, and the generated code is compiled by
+//! the same compiler as the derive, so an `msrv` older than 1.71 is rejected. Everything the
+//! derives currently generate compiles on 1.71, so newer versions don't change the output yet.
+//! 
+//! ```compile_fail
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! #[destructure(msrv = "1.56")]
+//! struct Foo(String);
+//! # impl Drop for Foo {
+//! #     fn drop(&mut self) {}
+//! # }
+//! # fn main() {}
+//! ```
+//! 
+//! The generated impls are put in an anonymous `const _: () = { ... };`, so that only the
+//! generated types, traits and macros are added to the module of the original type.
+//! 
+//! ## `#[destructure(unsafe_pin_ref)]` on a field
+//! 
+//! Fields of type `Pin<Box<T>>`, `Pin<Rc<T>>` and so on can be destructured like any other field,
+//! but destructuring a pinned reference (`Pin<&T>` or `Pin<&mut T>`) is rejected, because the
+//! skipped `drop()` method might be what the pinned value relies on to be cleaned up correctly.
+//! If you've checked that destructuring such a field is sound, mark it with
+//! `#[destructure(unsafe_pin_ref)]`.
+//! 
+//! ## `#[destructure(fields_macro)]`
+//! 
+//! Generates a `macro_rules!` macro named after the struct (`foo_fields!` for `Foo`, or the name
+//! given with `#[destructure(fields_macro = "...")]`) that passes the list of fields to another
+//! macro. `foo_fields!(my_macro)` expands to
+//! ```ignore
+//! my_macro! { some_str: String, some_int: i32 }
+//! ```
+//! This lets other macros generate code for every field without parsing the struct themselves.
+//! Like any `macro_rules!` macro, it can only be used below the struct definition.
+//! 
+//! ## `#[destructure(ext_trait)]`
+//! 
+//! Instead of an inherent method, the `destructure()` method (and its aliases) are generated as
+//! the only implementation of a new trait `FooDestructureExt`, which has the same visibility as
+//! `Foo`. The method is then only available where this trait is imported.
+//! 
+//! ## `#[destructure(field_by_name)]`
+//! 
+//! Generates a method `fn into_field_by_name(self, name: &str) -> Result<Box<dyn Any>, Foo>`
+//! that moves the field with the given name out of the struct and drops the other fields.
+//! If there's no field with that name, the struct is returned unchanged. Fields of tuple structs
+//! are named `"0"`, `"1"`, and so on. All fields need to be `'static`.
+//! 
+//! ## `#[destructure(fields_any_mut)]`
+//! 
+//! Generates a method `fn fields_any_mut(&mut self) -> [&mut dyn Any; N]`, where `N` is the
+//! number of fields, that returns mutable references to all fields in order. This lets code
+//! visit the fields of different types in the same way. All fields need to be `'static`.
+//! 
+//! ## `#[destructure(as_parts)]`
+//! 
+//! Generates a method `fn as_parts(&self) -> (&field_1, &field_2, ...)` that returns references
+//! to the same fields that `destructure()` returns, to look at all of them at once without
+//! consuming the struct.
+//! 
+//! ## `#[destructure(as_parts_mut)]`
+//! 
+//! Generates a method `fn as_parts_mut(&mut self) -> (&mut field_1, &mut field_2, ...)`, like
+//! `as_parts()` but with mutable references, to change several fields through one call. The
+//! struct stays whole, so `drop()` still runs when it goes out of scope.
+//! 
+//! ## `#[destructure(cloned)]`
+//! 
+//! Generates a method `fn destructure_cloned(&self) -> (field_1, field_2, ...)` that returns
+//! clones of the fields that `destructure()` returns, for example to take a snapshot for
+//! debugging. The struct isn't consumed, so it's dropped normally later. All returned fields
+//! need to be `Clone`.
+//! 
+//! ## `#[destructure(take_parts)]`
+//! 
+//! Generates a method `fn take_parts(&mut self) -> (field_1, field_2, ...)` that moves the fields
+//! that `destructure()` returns out of a `&mut Foo`, leaving their `Default` values behind. The
+//! struct stays valid, so `drop()` still runs on it later. This is useful in state machines that
+//! only have `&mut self`. All returned fields need to be `Default`.
+//! 
+//! ## `#[destructure(drop_in_place)]`
+//! 
+//! Generates an associated function `unsafe fn drop_original_in_place(this: *mut Foo)` that
+//! drops the value behind the pointer normally, calling `drop()` like `ptr::drop_in_place`
+//! does. Together with `destructure()`, this gives code that manages `Foo` through raw
+//! pointers (like a table of destructors for FFI) both ways to get rid of a value, generated
+//! from the same type. The same safety requirements as for `ptr::drop_in_place` apply.
+//! 
+//! ## `#[destructure(copy)]` on fields
+//! 
+//! `destructure()` copies fields of `Copy` types out of the struct in safe code, and only uses
+//! `unsafe` to move out the other fields. Primitive types, shared references, raw pointers,
+//! and tuples and arrays of those are recognized automatically. Add `#[destructure(copy)]` to a
+//! field of any other `Copy` type to do the same for it. If the type isn't `Copy` after all,
+//! this is a compile error rather than undefined behavior.
+//! 
+//! ## `#[destructure(skip)]` on fields
+//! 
+//! The field isn't returned by `destructure()`, but dropped normally instead, after `Foo` is
+//! taken apart. Everything else that describes the returned tuple, like `FooFields` or
+//! `FIELD_SIZES`, leaves the field out too.
+//! 
+//! ## `#[destructure(forget)]` on fields
+//! 
+//! Like `#[destructure(skip)]`, but the field is neither returned nor dropped: it's leaked, like
+//! with `mem::forget`. This is for fields whose resources are owned by something else by the
+//! time `destructure()` is called, like an OS handle that was handed over.
+//! 
+//! ## `#[destructure(flatten)]` on fields
+//! 
+//! If the type of the field also derives `destructure` with `#[destructure(impl_trait)]`,
+//! `destructure()` takes the field apart too, and returns the tuple of its fields in place of
+//! the field, so that neither `drop()` method runs. The fields are nested rather than spliced
+//! into the outer tuple, like `(a, (inner_1, inner_2), b)`, because the derive can't see the
+//! fields of another type. This uses the `Destructure` trait, so `derive_destructure_traits`
+//! must be a dependency. It can't be combined with the options that describe the returned
+//! fields with their own types, like `fields_type` or `from_parts`.
+//! 
+//! ## `#[destructure(fields_type)]`
+//! 
+//! Generates a type alias `FooFields` (or the name given with
+//! `#[destructure(fields_type = "...")]`) for the tuple returned by `destructure()`, with the
+//! same visibility and generic parameters as `Foo`. This lets you name the result in function
+//! signatures without repeating the whole tuple.
+//! 
+//! ## `#[destructure(parts_tuple)]`
+//! 
+//! Generates a wrapper `pub struct FooPartsTuple(pub (A, B, ...))` around the tuple returned by
+//! `destructure()`, whose `Debug` impl prints the fields with their names, like
+//! `Foo { some_str: "foo", some_int: 4 }`, instead of as an anonymous tuple. The wrapper has
+//! the same visibility as `Foo`. Wrap the result in it when logging:
+//! `println!("{:?}", FooPartsTuple(foo.destructure()))`.
+//! 
+//! ## `#[destructure(impl_trait)]`
+//! 
+//! Implements the `Destructure` trait of the companion crate
+//! [`derive_destructure_traits`](https://docs.rs/derive_destructure_traits), in addition to the
+//! inherent method. This lets generic code take any such type apart:
+//! `fn consume<T: Destructure>(t: T) -> T::Fields { t.destructure() }`. The associated type
+//! `Fields` is the returned tuple, or `FooDestructured` for an enum. Add
+//! `derive_destructure_traits` to your dependencies to use this.
+//! 
+//! The companion crate also has `destructure_ok(result)` and `destructure_some(option)`, which
+//! take apart the value inside a `Result` or an `Option` and return its fields in the same
+//! wrapper, for teardown code that would otherwise unwrap the value first.
+//! 
+//! ## `#[destructure(into_tuple)]`
+//! 
+//! Implements `From<Foo>` for the tuple returned by `destructure()`, so that `Foo` can be taken
+//! apart with `.into()` or passed where `Into<(T1, T2, ...)>` is expected. Unlike the generated
+//! methods, which are private, the impl is as public as the types involved, so it's a way to
+//! offer destructuring in a public API. This can't be combined with `unsafe_fn`.
+//! 
+//! ## `#[destructure(from_parts)]`
+//! 
+//! Generates the inverse of `destructure()`: `fn from_parts(parts: (A, B, ...)) -> Foo`, which
+//! builds `Foo` from the tuple that `destructure()` returns. This is useful to take a value
+//! apart, replace one of its fields, and put it back together, without writing a struct
+//! expression that has to change whenever the fields do. This doesn't work with skipped or
+//! forgotten fields.
+//! 
+//! ## `#[destructure(named)]`
+//! 
+//! Generates a struct `FooParts` with the same fields as `Foo` (minus the skipped ones), and a
+//! method `fn destructure_named(self) -> FooParts`. Since the fields keep their names, the
+//! result can be destructured with a struct pattern, like
+//! `let FooParts { some_str, some_int } = foo.destructure_named();`, which is harder to get
+//! wrong than a tuple with many fields of the same type. `FooParts` and its fields have the
+//! same visibility as `Foo`. This doesn't work on tuple structs.
+//! 
+//! `FooParts` only has the generic parameters of `Foo` that its fields use, so if the only
+//! field that uses `T` is skipped, `FooParts` has no `T`. The same goes for the other generated
+//! types that only hold the returned fields, like `FooFields`, `FooWithout<Field>` and
+//! `FooPartsTuple`.
+//! 
+//! On an enum, it keeps the field names of struct-like variants in `FooDestructured`, so that
+//! `Foo::A { x, y }` becomes `FooDestructured::A { x, y }` instead of `FooDestructured::A(x, y)`.
+//! 
+//! ## `#[destructure(threshold = 12)]`
+//! 
+//! A tuple with many fields is hard to use, so if `Foo` has more than the given number of
+//! fields (minus the skipped ones), `destructure()` and its aliases return `FooParts` instead,
+//! like `destructure_named()` does with `#[destructure(named)]`. `threshold = 0` always returns
+//! `FooParts`. This doesn't work on tuple structs.
+//! 
+//! ## `#[destructure(into_struct = "...")]`
+//! 
+//! Makes `destructure()` and its aliases return an existing struct instead of a tuple, like
+//! `#[destructure(into_struct = "FooData<T>")]`. Every returned field of `Foo` is moved into the
+//! field with the same name, so the struct must have exactly those fields, with the same types.
+//! This doesn't work on tuple structs, and can't be combined with `threshold`.
+//! 
+//! ## `#[destructure(const_fn)]`
+//! 
+//! Makes `destructure()` a `const fn`, along with its aliases, `destructure_named()` and
+//! `from_parts()`, so that a value can be taken apart in a constant. This doesn't work
+//! with with `ext_trait` or with skipped fields, because their destructors can't run
+//! in a constant.
+//! 
+//! ## `#[destructure(unsafe_fn)]`
+//! 
+//! Declares `destructure()` as an `unsafe fn`, along with the other generated methods that
+//! bypass `Drop`: the aliases, `destructure_named()`, `destructure_with_shell()`,
+//! `into_field_by_name()` and `try_into_<variant>()`. Use this for types whose destructor does
+//! something that callers must not skip by accident, like releasing a lock, so that every call
+//! has to be in an `unsafe` block. This can't be combined with `impl_trait`.
+//! 
+//! ## `#[destructure(before = "...")]`
+//! 
+//! Calls a function before the struct is taken apart, like `before = "Self::flush"` for a
+//! method `fn flush(&mut self)`. It's called by `destructure()` and by every other generated
+//! method that takes the struct apart, so that callers can't forget it. If it panics, the struct
+//! is dropped normally.
+//! 
+//! ## `#[destructure(allow_no_drop)]`
+//! 
+//! `#[derive(destructure)]` checks at compile time that the type implements `Drop`, because a
+//! type without a destructor can be taken apart with a plain `let`, and `destructure()` would
+//! silently skip a `Drop` impl that's added to it later. A type without `Drop` gets a
+//! deprecation warning, which will become an error in the next major version. This option turns
+//! the check off, for types that deliberately don't implement `Drop` (yet).
+//! 
+//! ```compile_fail
+//! #![deny(deprecated)]
+//! # #[macro_use]
+//! # extern crate derive_destructure;
+//! #[derive(destructure)]
+//! struct Plain {
+//!     name: String,
+//! }
+//! # fn main() {}
+//! ```
+//! 
+//! ## `#[destructure(zeroize)]`
+//! 
+//! Calls `zeroize::Zeroize` on the bytes of the struct after its fields have been moved out, for
+//! structs that hold secrets like key material. The generated methods move `self` into a
+//! `ManuallyDrop<Self>` and read the fields out of it, and that `ManuallyDrop` is the memory that
+//! gets wiped. With `boxed`, it's the heap allocation of the `Box`, before it's freed. With
+//! `with_shell`, it's the `MaybeUninit<Self>` that `destructure_with_shell()` returns, after the
+//! skipped fields have been dropped.
+//! 
+//! Nothing else is wiped. Moving the struct into the method leaves the bytes it was moved from
+//! behind (in the caller's variable, for example), and the returned fields are moved again, so
+//! the secrets may still be copied elsewhere on the stack. The crate with the struct needs a
+//! dependency on `zeroize`. This doesn't work on enums and can't be combined with `const_fn`.
+//! 
+//! ## `#[destructure(instrument = "log")]` and `#[destructure(instrument = "tracing")]`
+//! 
+//! Emits a debug event every time a generated method bypasses `Drop`, with the name of the type
+//! and the location that the method was called from, so that leaks caused by a value that was
+//! taken apart and never put back together can be tracked down. The event goes to `::log::debug!`
+//! or to `::tracing::debug!`, so the crate with the struct needs a dependency on the chosen
+//! crate. This doesn't work on enums and can't be combined with `const_fn`.
+//! 
+//! ## `#[destructure(guard)]`
+//! 
+//! Generates a wrapper `struct FooGuard(Foo)` for values that may or may not be taken apart
+//! later. `FooGuard::new(foo)` wraps a value, `into_parts()` takes it apart like `destructure()`,
+//! and `into_original()` gives it back. A guard that's simply dropped drops the `Foo` inside,
+//! which runs its `drop()` as usual, so the cleanup isn't lost if the value is never taken
+//! apart. The guard derefs to `Foo`, and has the same visibility and generic parameters. Like
+//! the other generated methods, its methods are private.
+//! 
+//! ## `#[destructure(with_fields)]`
+//! 
+//! Generates a method `fn with_fields<R>(self, f: impl FnOnce(field_1, field_2, ...) -> R) -> R`
+//! that takes the struct apart like `destructure()` and passes the fields straight to the
+//! closure, for example `foo.with_fields(|some_str, some_int| format!("{}{}", some_str, some_int))`.
+//! 
+//! ## `#[destructure(with_methods)]`
+//! 
+//! Generates a method `fn with_<field>(self, value: Field) -> Foo` for every field, that returns
+//! the struct with that field replaced, like `let foo = foo.with_some_int(5);`. The struct isn't
+//! dropped along the way, so its `drop()` only runs once, when the returned value is dropped. The
+//! old value of the field is dropped. This doesn't work on tuple structs.
+//! 
+//! ## `#[destructure(replace_methods)]`
+//! 
+//! Generates a method `fn replace_<field>(&mut self, value: Field) -> Field` for every field,
+//! that puts `value` in the field and returns the old value, like `mem::replace`. This moves a
+//! single field out from behind `&mut self`, without `unsafe` code and without taking the whole
+//! struct apart. This doesn't work on tuple structs.
+//! 
+//! ## `#[destructure(rest)]`
+//! 
+//! Generates a method `fn take_<field>(self) -> (Field, FooWithout<Field>)` for every field, which
+//! takes that one field out and keeps the others together. For example,
+//! `let (some_str, rest) = foo.take_some_str();` returns a `FooWithoutSomeStr` with all fields of
+//! `Foo` except `some_str` (and the skipped ones), with the same types that `destructure()` returns
+//! them as. These structs have no `Drop` impl, so dropping one drops the remaining fields without
+//! calling `Foo`'s `drop()`. This doesn't work on tuple structs.
+//! 
+//! ## `#[destructure(justification = "...")]` and `#[remove_trait_impls(justification = "...")]`
+//! 
+//! Records why it's fine to bypass `Drop` for this type. The text is put in the documentation
+//! of `destructure()` (or of `FooWithoutTraitImpls`), and in an associated constant
+//! `Foo::DESTRUCTURE_JUSTIFICATION` (or `Foo::REMOVE_TRAIT_IMPLS_JUSTIFICATION`), so that
+//! reviewers and tools can find it.
+//! 
+//! A crate can make the justification mandatory for its own types by compiling with
+//! `--cfg derive_destructure_require_justification`, for example from its build script:
+//! 
+//! ```ignore
+//! fn main() {
+//!     println!("cargo:rustc-cfg=derive_destructure_require_justification");
+//! }
+//! ```
+//! 
+//! Then it's an error to use either derive in that crate without a justification. Other crates
+//! in the same build, such as dependencies that also use derive_destructure, aren't affected.
+//! 
+//! ## `#[remove_trait_impls(keep_derives)]`
+//! 
+//! Derives the same traits for `FooWithoutTraitImpls` as for `Foo`, so that it behaves like
+//! `Foo` without `Drop`. Only the `#[derive(...)]` attributes that come after
+//! `#[derive(remove_trait_impls)]` can be seen, so these need to be written in a separate
+//! attribute below it. Derives that don't make sense for the generated type can be left out with
+//! `keep_derives(except(Serialize, Hash))`.
+//! 
+//! ## `#[remove_trait_impls(derive(Debug, Clone, PartialEq))]`
+//! 
+//! Derives the listed traits for `FooWithoutTraitImpls`, which otherwise implements none. This
+//! is simpler than `variants(...)` when only the main generated type needs them.
+//! 
+//! ## `#[remove_trait_impls(forward_field_attrs(serde))]`
+//! 
+//! Copies the listed attributes of the fields and variants of `Foo` onto those of
+//! `FooWithoutTraitImpls`, for example to keep `#[serde(rename = "...")]` when deriving
+//! `Serialize` with `derive(...)` above. Doc comments are always copied.
+//! 
+//! ## `#[remove_trait_impls(unsafe_impl(Send, Sync))]`
+//! 
+//! The generated `FooWithoutTraitImpls` type only implements `Send` and `Sync` if the compiler
+//! can prove that all of its fields do. If `Foo` has hand-written `unsafe impl`s of such marker
+//! traits (for example because it contains raw pointers), then
+//! `#[remove_trait_impls(unsafe_impl(...))]` emits the same `unsafe impl`s for
+//! `FooWithoutTraitImpls`. Like any `unsafe impl`, it's up to you to make sure this is sound.
+//! 
+//! ## `#[remove_trait_impls(debug)]`
+//! 
+//! Implements `Debug` for the generated type, printing all of its fields. Fields listed in
+//! `#[remove_trait_impls(debug(redact(password, token)))]` are printed as `***` instead, which
+//! is useful for logging the generated value without leaking secrets. Fields of tuple structs
+//! and tuple variants are listed by index, like `redact(0)`.
+//! 
+//! ## `#[remove_trait_impls(keep(Debug, PartialEq, Eq, Hash))]`
+//! 
+//! Implements the listed traits for the generated type by delegating to its fields one by one,
+//! like `#[derive(...)]` would, even if `Foo` implements them by hand. `Debug` prints the fields
+//! like `#[remove_trait_impls(debug)]`, `PartialEq` compares them pairwise, and `Hash` hashes
+//! them in order, after the variant for enums. Only these traits can be listed: a trait like
+//! `Display` has no field-by-field form, so it has to be implemented by hand. This doesn't work
+//! on unions or `#[repr(packed)]` structs, and can't be combined with `repr_c_tagged`.
+//! 
+//! ## `#[remove_trait_impls(assert_layout)]`
+//! 
+//! Checks at compile time that `FooWithoutTraitImpls` has the same size and alignment as `Foo`,
+//! for code that relies on that. For generic types, the check happens for every instantiation
+//! on which `remove_trait_impls()` is called.
+//! 
+//! ## `#[remove_trait_impls(try_restore(validate = "...", error = "..."))]`
+//! 
+//! Generates a method `fn try_restore(self) -> Result<Foo, (FooWithoutTraitImpls, Error)>` on
+//! the generated type, which turns it back into the original type if the given validator, a
+//! function `fn(&FooWithoutTraitImpls) -> Result<(), Error>`, accepts it. This lets you re-check
+//! the invariants of `Foo` after modifying the fields of `FooWithoutTraitImpls`.
+//! 
+//! ## `#[remove_trait_impls(reverse_from)]`
+//! 
+//! Implements `From<FooWithoutTraitImpls>` for `Foo`, which moves the fields back into the
+//! original type, so `Foo::from(foo.remove_trait_impls())` gives back the same value. The
+//! original type's `drop()` method will run again when the result is dropped. This doesn't
+//! work on unions.
+//! 
+//! ## `#[remove_trait_impls(impl_trait)]`
+//! 
+//! Implements the `RemoveTraitImpls` trait of `derive_destructure_traits`, whose associated type
+//! `Output` is `FooWithoutTraitImpls`. Like `#[destructure(impl_trait)]`, this is for generic
+//! code, which can then strip the trait impls of any such type without naming the generated
+//! type.
+//! 
+//! ## `#[remove_trait_impls(owned)]`
+//! 
+//! Turns borrowed fields into owned ones: a field of type `&T`, `&mut T` or `Cow<T>` becomes
+//! `<T as ToOwned>::Owned` in `FooWithoutTraitImpls`, so `&str` becomes `String` and `&[T]`
+//! becomes `Vec<T>`. The values are copied with `ToOwned` in `remove_trait_impls()`. The
+//! generated type has no lifetime parameters, so it's `'static` if the other fields are, which
+//! means that lifetimes may only be used in those borrowed fields. This can't be combined with
+//! `assert_layout`, `try_restore` or `reverse_from`.
+//! 
+//! ## `#[remove_trait_impls(variants(NoDrop(keep_derives), Bare))]`
+//! 
+//! Generates additional types without trait impls next to `FooWithoutTraitImpls`, named after
+//! the original type plus the given suffix, along with a method to convert into each of them.
+//! Here, `Foo` gets `fn into_no_drop(self) -> FooNoDrop` and `fn into_bare(self) -> FooBare`.
+//! Each of them can derive traits again:
+//! 
+//! * `keep_derives` derives the same traits as `Foo`, like `#[remove_trait_impls(keep_derives)]`
+//!   does for `FooWithoutTraitImpls`, and `keep_derives(except(...))` leaves some out.
+//! * `derive(Clone, Debug)` derives the listed traits.
+//! 
+//! ## `#[remove_trait_impls(repr_c_tagged)]`
+//! 
+//! For an enum `Foo`, generates `FooWithoutTraitImpls` as a `#[repr(C)]` struct that C code can
+//! read directly, instead of as an enum. It has two fields: `tag`, of a fieldless enum
+//! `FooWithoutTraitImplsTag` with the same variants (and discriminants and `#[repr(...)]`
+//! attribute) as `Foo`, and `payload`, a `#[repr(C)]` union `FooWithoutTraitImplsPayload` with a
+//! `ManuallyDrop` field for every variant. The field for variant `A` is named `A`, and holds a
+//! `#[repr(C)]` struct `FooWithoutTraitImplsA` with the fields of that variant. Only the field
+//! of the variant in `tag` may be read. This doesn't work on generic enums, can't be combined
+//! with `debug`, `assert_layout`, `try_restore` or `reverse_from`.
+//! 
+//! ## `#[remove_trait_impls(module = "...")]`
+//! 
+//! Generates `FooWithoutTraitImpls`, and the other generated types and their impls, in a new
+//! module with the given name next to `Foo`, so that they don't fill up the module of `Foo`.
+//! With `module = "parts"`, the type is `parts::FooWithoutTraitImpls`. The module is as visible
+//! as `Foo`, and the generated types and fields are as visible from outside the module as they
+//! would have been without it. The generated methods remain callable wherever they would have
+//! been. The module imports everything from its parent with `use super::*`, so the field types
+//! can't use relative paths starting with `self::` or `super::`. Each type needs a module of its
+//! own.
+//! 
+//! ## `#[destructure(crate_path = "...")]` and `#[remove_trait_impls(crate_path = "...")]`
+//! 
+//! The generated code refers to the standard library with paths like `::core::ptr::read` and
+//! `::core::mem::ManuallyDrop`. If `core` (or `std`) is only available under a different path,
+//! for example through a facade crate that re-exports it, set that path with
+//! `crate_path = "::my_facade"`, and the generated code will use `::my_facade::ptr::read`
+//! instead. It can be given in either attribute, and applies to the code of both derives.
+//! 
+//! ## `#[destructure(no_std)]` and `#[remove_trait_impls(no_std)]`
+//! 
+//! Most of the generated code only uses `::core`, so it works in `#![no_std]` crates as well as
+//! in crates that use `std`. The options that need an allocator (`field_by_name`, `boxed` and
+//! `#[remove_trait_impls(owned)]`) take `Box`, `Cow` and `ToOwned` from `::std` by default. In a
+//! `#![no_std]` crate, set `no_std` to take them from `::alloc` instead, which also needs
+//! `extern crate alloc;`. Like `crate_path`, it can be given in either attribute, applies to the
+//! code of both derives, and only affects the type it's on.
+//! 
+//! # Lints
+//! 
+//! The generated types and impls are marked `#[allow(dead_code, missing_docs, ...)]` (along with
+//! a few Clippy lints), and the impls `#[automatically_derived]`, so that unused generated methods
+//! and undocumented generated types don't show up in the lint reports of the crate that uses the
+//! derives. The lints still apply to the original type.
+//! 
+//! The generated methods use `unsafe` code to move the fields out, but that code comes from
+//! this crate's macros, so it isn't reported by `#![deny(unsafe_code)]` or
+//! `#![forbid(unsafe_code)]` in the crate that uses the derives, and those keep applying to that
+//! crate's own code. The generated items aren't marked `#[allow(unsafe_code)]`, because
+//! `forbid` would reject that.
+//! 
+//! # Features
+//! 
+//! * `verbose-errors`: errors in the input of the derives also say which type and field they're
+//!   about (with the type of the field as written), and suggest a fix.
 
 // The `quote!` macro requires deep recursion.
 #![recursion_limit = "512"]
 
 extern crate proc_macro;
 
-use proc_macro2::{Ident, Span};
-use quote::{quote, quote_spanned};
+mod attr;
+mod error;
+mod macros;
+
+use std::mem;
+
+use proc_macro2::{Group, Ident, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{parse_macro_input, DeriveInput, Data, Fields, Index};
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_macro_input, parse_quote, Attribute, DeriveInput, Data, DataEnum, ExprPath, Field, Fields, GenericArgument, GenericParam, Generics, Index, Item, Macro, Member, Meta, Path, PathArguments, QSelf, Token, Type, TypeParamBound, TypePath, Variant, Visibility, WherePredicate};
+
+use crate::attr::{crate_path, no_std, DestructureFieldOpts, DestructureOpts, Inline, Instrument, RemoveTraitImplsOpts};
+use crate::error::{ErrorContext, Errors};
+
+/// Takes a struct apart into variables named after its fields, like
+/// `destructure_let!(Foo { some_str, some_int } = foo);`. See the crate documentation.
+#[proc_macro]
+pub fn destructure_let(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as macros::DestructureLet);
+    proc_macro::TokenStream::from(macros::destructure_let(input))
+}
+
+/// Takes some fields out of a struct and drops the rest, like
+/// `destructure_some!(foo => { some_str, .. });`. See the crate documentation.
+#[proc_macro]
+pub fn destructure_some(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as macros::DestructureSome);
+    proc_macro::TokenStream::from(macros::destructure_some(input))
+}
+
+/// Matches on an enum without running its `drop()` method, like
+/// `destructure_match!(foo { Foo::A(x) => x, Foo::B => 0 })`. See the crate documentation.
+#[proc_macro]
+pub fn destructure_match(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    proc_macro::TokenStream::from(macros::destructure_match(TokenStream::from(input)))
+}
 
-#[proc_macro_derive(destructure)]
+#[proc_macro_derive(destructure, attributes(destructure))]
 pub fn derive_destructure(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
+    proc_macro::TokenStream::from(expand_destructure(input).unwrap_or_else(syn::Error::into_compile_error))
+}
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+/// The same as `#[derive(destructure)]`.
+#[proc_macro_derive(Destructure, attributes(destructure))]
+pub fn derive_destructure_pascal_case(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_destructure(input)
+}
 
-    let output = match input.data {
-        Data::Struct(ref data) => {
-            match data.fields {
-                Fields::Named(ref fields) => {
-                    let field_types = fields.named.iter().map(|f| {
-                        let ty = &f.ty;
-                        quote_spanned! {f.span()=>
-                            #ty
-                        }
-                    });
-                    let field_reads = fields.named.iter().map(|f| {
-                        let ident = &f.ident;
-                        quote_spanned! {f.span()=>
-                            ::std::ptr::read(&self_ref.#ident)
-                        }
-                    });
-                    quote! {
-                        impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn destructure(self) -> (#(#field_types,)*) {
-                                let maybe_uninit = ::std::mem::MaybeUninit::new(self);
-                                unsafe {
-                                    let self_ref = &*maybe_uninit.as_ptr();
-                                    (#(#field_reads,)*)
-                                }
-                            }
+fn expand_destructure(input: DeriveInput) -> syn::Result<TokenStream> {
+    let cx = ErrorContext::new(&input);
+    let opts = destructure_opts(&input)?;
+    let mut errors = Errors::default();
+    if opts.unsafe_fn && opts.impl_trait {
+        errors.push(cx.error(
+            "#[destructure(unsafe_fn)] can't be combined with impl_trait, because the method of the Destructure trait is safe.".to_owned(),
+            "remove either unsafe_fn or impl_trait",
+        ));
+    }
+    if opts.unsafe_fn && opts.into_tuple {
+        errors.push(cx.error(
+            "#[destructure(unsafe_fn)] can't be combined with into_tuple, because From::from is safe.".to_owned(),
+            "remove either unsafe_fn or into_tuple",
+        ));
+    }
+
+    let drop_check = if opts.allow_no_drop {
+        TokenStream::new()
+    } else {
+        destructure_drop_check(&input)
+    };
+
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        Data::Enum(ref data) => {
+            errors.finish()?;
+            let output = destructure_enum(&input, data, &opts)?;
+            return Ok(finish_output(quote!(#output #drop_check)));
+        }
+        Data::Union(_) => return Err(cx.error(
+            "#[derive(destructure)] doesn't work on unions, because the active field isn't known. Use #[derive(remove_trait_impls)] instead.".to_owned(),
+            "replace #[derive(destructure)] with #[derive(remove_trait_impls)], and read the active field out of the generated union",
+        )),
+    };
+
+    errors.check(check_pin_refs(&input, fields));
+    errors.check(check_unsized_fields(&input, fields));
+    if let Some(option) = opts.borrowing_option().filter(|_| is_packed(&input)) {
+        errors.push(cx.error(
+            format!("#[destructure({})] doesn't work on #[repr(packed)] structs, because their fields can't be borrowed.", option),
+            &format!("remove {} from #[destructure(...)]", option),
+        ));
+    }
+    if opts.try_into_variants {
+        errors.push(cx.error(
+            "#[destructure(try_into_variants)] only works on enums.".to_owned(),
+            "remove try_into_variants from #[destructure(...)]",
+        ));
+    }
+    if let Some(option) = opts.unflattened_option() {
+        for f in fields.iter() {
+            if field_opts(&input, f).flatten {
+                errors.push(cx.with_field(f).error(
+                    format!("#[destructure({})] can't be combined with #[destructure(flatten)] fields, because it uses the type of the field itself.", option),
+                    &format!("remove {} from #[destructure(...)], or #[destructure(flatten)] from the field", option),
+                ));
+            }
+        }
+    }
+    if opts.into_struct.is_some() {
+        if let Fields::Unnamed(_) = *fields {
+            errors.push(cx.error(
+                "#[destructure(into_struct = \"...\")] doesn't work on tuple structs, because the fields are moved by name.".to_owned(),
+                "remove into_struct from #[destructure(...)]",
+            ));
+        }
+        if opts.threshold.is_some() {
+            errors.push(cx.error(
+                "#[destructure(into_struct = \"...\")] can't be combined with threshold, because both change what destructure() returns.".to_owned(),
+                "remove either into_struct or threshold",
+            ));
+        }
+    }
+    if opts.zeroize {
+        errors.check(check_zeroize(&input, &opts));
+    }
+    if opts.instrument.is_some() {
+        errors.check(check_instrument(&input, &opts));
+    }
+    if let (Some(_), &Fields::Unnamed(_)) = (opts.threshold, fields) {
+        errors.push(cx.error(
+            "#[destructure(threshold = ...)] doesn't work on tuple structs, because their fields have no names for FooParts.".to_owned(),
+            "remove threshold from #[destructure(...)]",
+        ));
+    }
+    errors.finish()?;
+
+    let returned = returned_fields(&input, fields);
+    let output = destructure_struct(&input, fields, &opts)?;
+    let patch = if opts.patch {
+        destructure_patch(&input, fields, &opts)
+    } else {
+        TokenStream::new()
+    };
+    let field_layout = if opts.field_layout {
+        destructure_field_layout(&input, &returned)
+    } else {
+        TokenStream::new()
+    };
+    let field_info = if opts.field_info {
+        destructure_field_info(&input, &returned)
+    } else {
+        TokenStream::new()
+    };
+    let parts_layout = if opts.parts_layout {
+        destructure_parts_layout(&input, &returned, &opts)
+    } else {
+        TokenStream::new()
+    };
+    let fields_macro = match opts.fields_macro {
+        Some(ref macro_name) => destructure_fields_macro(&input, &returned, macro_name.as_ref()),
+        None => TokenStream::new(),
+    };
+    let fields_type = match opts.fields_type {
+        Some(ref type_name) => destructure_fields_type(&input, &returned, type_name.as_ref()),
+        None => TokenStream::new(),
+    };
+    let justification = justification_const(&input, opts.justification.as_ref(), "destructure", "DESTRUCTURE_JUSTIFICATION");
+    let parts_tuple = if opts.parts_tuple {
+        destructure_parts_tuple(&input, &returned)
+    } else {
+        TokenStream::new()
+    };
+    let named_parts = if opts.named || opts.returns_parts_struct(returned.len()) {
+        destructure_named_parts(&input, &returned)?
+    } else {
+        TokenStream::new()
+    };
+    let rest = if opts.rest {
+        destructure_rest(&input, &returned)?
+    } else {
+        TokenStream::new()
+    };
+    let impl_trait = if opts.impl_trait {
+        let field_types = returned.iter().map(|&(f, _)| returned_type(&input, f));
+        let body = consume_fields(&input, fields, &opts);
+        destructure_trait_impl(&input, quote!((#(#field_types,)*)), body, opts.inline)
+    } else {
+        TokenStream::new()
+    };
+    let into_tuple = if opts.into_tuple {
+        destructure_into_tuple(&input, &returned, &opts)
+    } else {
+        TokenStream::new()
+    };
+
+    let output = quote! {
+        #output
+        #patch
+        #field_layout
+        #field_info
+        #parts_layout
+        #fields_macro
+        #fields_type
+        #parts_tuple
+        #named_parts
+        #rest
+        #impl_trait
+        #into_tuple
+        #justification
+        #drop_check
+    };
+    Ok(finish_output(output))
+}
+
+/// Parses the options of `#[destructure(...)]` on the type and on all of its fields, and the
+/// paths that the generated code uses, so that the errors in all of them are reported at once.
+/// After that, `field_opts`, `core_path` and `alloc_path` can read them again without errors.
+fn destructure_opts(input: &DeriveInput) -> syn::Result<DestructureOpts> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    let opts = errors.check(DestructureOpts::from_attrs(cx, &input.attrs));
+    errors.check(crate_path(cx, &input.attrs));
+    errors.check(no_std(cx, &input.attrs));
+    for f in all_fields(input) {
+        errors.check(DestructureFieldOpts::from_attrs(cx.with_field(f), &f.attrs));
+    }
+    errors.finish()?;
+    Ok(opts.unwrap_or_default())
+}
+
+/// Parses `#[remove_trait_impls(...)]` and the type-level attributes it shares with
+/// `#[destructure(...)]`, so that all the errors in them are reported at once.
+fn remove_trait_impls_opts(input: &DeriveInput) -> syn::Result<RemoveTraitImplsOpts> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    let opts = errors.check(RemoveTraitImplsOpts::from_attrs(cx, &input.attrs));
+    errors.check(crate_path(cx, &input.attrs));
+    errors.check(no_std(cx, &input.attrs));
+    errors.finish()?;
+    Ok(opts.unwrap_or_default())
+}
+
+/// Returns the fields of a struct or union, or the fields of all variants of an enum.
+fn all_fields(input: &DeriveInput) -> Vec<&Field> {
+    match input.data {
+        Data::Struct(ref data) => data.fields.iter().collect(),
+        Data::Enum(ref data) => data.variants.iter().flat_map(|variant| variant.fields.iter()).collect(),
+        Data::Union(ref data) => data.fields.named.iter().collect(),
+    }
+}
+
+/// The options of `#[destructure(...)]` on the field `f`. `destructure_opts` has already reported
+/// any errors in them, so this is only called when there are none.
+fn field_opts(input: &DeriveInput, f: &Field) -> DestructureFieldOpts {
+    DestructureFieldOpts::from_attrs(ErrorContext::new(input).with_field(f), &f.attrs).unwrap_or_default()
+}
+
+/// Generates the compile-time check that the type implements `Drop`. A type without a
+/// destructor can simply be destructured with a `let`, so deriving `destructure` on it is most
+/// likely a mistake, and would silently skip a `Drop` impl that's added later. The check is a
+/// method that's never called, so that `Self` has the generics of the type. It calls `check()`
+/// on a `&Check<Self>`, which finds `ImplementsDrop::check` first if `Self: Drop`, and otherwise
+/// falls back to the deprecated `WithoutDrop::check`, so that the type gets a warning rather
+/// than an error that would break code that compiled before the check was added.
+fn destructure_drop_check(input: &DeriveInput) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let note = format!("`{}` derives `destructure`, but doesn't implement `Drop`. A type without a destructor can be destructured with a `let`. Add #[destructure(allow_no_drop)] if it's deliberate, because this will be an error in the next major version of derive_destructure.", name);
+    let check = quote_spanned! {name.span()=>
+        (&Check::<Self>(|_| {})).check();
+    };
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[allow(dead_code)]
+            fn __destructure_assert_drop() {
+                struct Check<T: ?Sized>(fn(&T));
+                trait ImplementsDrop {
+                    fn check(&self) {}
+                }
+                #[allow(drop_bounds)]
+                impl<T: ?Sized + Drop> ImplementsDrop for Check<T> {}
+                trait WithoutDrop {
+                    #[deprecated(note = #note)]
+                    fn check(&self) {}
+                }
+                impl<T: ?Sized> WithoutDrop for &Check<T> {}
+                #check
+            }
+        }
+    }
+}
+
+/// The lints that are allowed on everything the derives generate, because they're about code that
+/// the user can't change: the generated methods and types may never be used, aren't documented,
+/// and are written the way the macro needs rather than the way a lint prefers.
+const ALLOWED_LINTS: &[&str] = &[
+    "dead_code",
+    "missing_docs",
+    "unused_qualifications",
+    "unused_unsafe",
+];
+
+/// The Clippy lints that are allowed on everything the derives generate, like `ALLOWED_LINTS`.
+const ALLOWED_CLIPPY_LINTS: &[&str] = &[
+    "needless_lifetimes",
+    "must_use_candidate",
+    "missing_const_for_fn",
+    "missing_docs_in_private_items",
+    "missing_inline_in_public_items",
+    "inline_always",
+    "used_underscore_binding",
+    "undocumented_unsafe_blocks",
+    "multiple_unsafe_ops_per_block",
+    "ptr_as_ptr",
+    "borrow_as_ptr",
+    "type_complexity",
+    "too_many_arguments",
+];
+
+/// Parses the generated code, and passes it through `allow_lints` and `anonymous_impls`. The
+/// generated code should always parse, so if it doesn't, that's reported as a bug instead of
+/// leaving out the lint allows and the anonymous constant.
+fn finish_output(output: TokenStream) -> TokenStream {
+    match syn::parse2::<syn::File>(output) {
+        Ok(mut file) => {
+            allow_lints(&mut file);
+            anonymous_impls(file)
+        }
+        Err(err) => syn::Error::new(
+            err.span(),
+            format!("derive_destructure generated code that it can't parse ({}). This is a bug in derive_destructure.", err),
+        ).to_compile_error(),
+    }
+}
+
+/// Adds `#[allow(...)]` with the lints in `ALLOWED_LINTS` and `ALLOWED_CLIPPY_LINTS` to every item
+/// in `file`, and `#[automatically_derived]` to the impls, so that the generated code doesn't
+/// show up in the lint reports of the crate that uses the derives.
+fn allow_lints(file: &mut syn::File) {
+    let mut lints: Vec<TokenStream> = ALLOWED_LINTS.iter().map(|lint| {
+        let lint = Ident::new(lint, Span::call_site());
+        quote!(#lint)
+    }).collect();
+    lints.push(quote!(unknown_lints));
+    lints.extend(ALLOWED_CLIPPY_LINTS.iter().map(|lint| {
+        let lint = Ident::new(lint, Span::call_site());
+        quote!(clippy::#lint)
+    }));
+    let allow: Attribute = parse_quote!(#[allow(#(#lints),*)]);
+    for item in &mut file.items {
+        let attrs = match *item {
+            Item::Impl(ref mut item) => {
+                item.attrs.push(parse_quote!(#[automatically_derived]));
+                &mut item.attrs
+            }
+            Item::Struct(ref mut item) => &mut item.attrs,
+            Item::Enum(ref mut item) => &mut item.attrs,
+            Item::Union(ref mut item) => &mut item.attrs,
+            Item::Type(ref mut item) => &mut item.attrs,
+            Item::Trait(ref mut item) => &mut item.attrs,
+            Item::Const(ref mut item) => &mut item.attrs,
+            Item::Mod(ref mut item) => &mut item.attrs,
+            Item::Macro(ref mut item) => &mut item.attrs,
+            _ => continue,
+        };
+        attrs.push(allow.clone());
+    }
+}
+
+/// Moves the impls in `file` into an anonymous `const _: () = { ... };`, so that only the
+/// generated types, traits and macros end up in the caller's module, and nothing else that the
+/// impls might need can clash with it.
+fn anonymous_impls(file: syn::File) -> TokenStream {
+    let (impls, items): (Vec<Item>, Vec<Item>) = file.items.into_iter().partition(|item| matches!(*item, Item::Impl(_)));
+    if impls.is_empty() {
+        return quote!(#(#items)*);
+    }
+    quote! {
+        #(#items)*
+        const _: () = {
+            #(#impls)*
+        };
+    }
+}
+
+/// Checks that no field is a pinned reference, unless it's marked `#[destructure(unsafe_pin_ref)]`.
+fn check_pin_refs(input: &DeriveInput, fields: &Fields) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    for (f, member) in fields.iter().zip(field_members(fields)) {
+        let ty = &f.ty;
+        if is_pin_ref(ty) && !field_opts(input, f).unsafe_pin_ref {
+            errors.push(cx.with_field(f).error(
+                format!(
+                    "Field `{}` of `{}` is a pinned reference (`{}`). Skipping the drop() method of `{}` \
+                    may break guarantees that the pinned value relies on. If you've checked that this \
+                    is sound, add #[destructure(unsafe_pin_ref)] to the field.",
+                    quote!(#member), input.ident, quote!(#ty), input.ident
+                ),
+                &format!("#[destructure(unsafe_pin_ref)] {}: {}", quote!(#member), quote!(#ty)),
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// Checks that no field is obviously unsized, like `[u8]`, `str` or `dyn Trait`. A struct with
+/// such a field can't be passed by value, so without this check, the errors would point at the
+/// generated code instead. The check is only syntactic, so `T: ?Sized` fields get through.
+fn check_unsized_fields(input: &DeriveInput, fields: &Fields) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    for (f, member) in fields.iter().zip(field_members(fields)) {
+        let ty = &f.ty;
+        if is_unsized_type(ty) {
+            errors.push(cx.with_field(f).error(
+                format!(
+                    "Field `{}` of `{}` is unsized (`{}`), but only structs with sized fields can be \
+                    taken apart by value.",
+                    quote!(#member), input.ident, quote!(#ty)
+                ),
+                &format!("store the field behind a pointer, like `{}: Box<{}>`", quote!(#member), quote!(#ty)),
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// Whether `ty` is obviously unsized: a slice, `str`, or a trait object written with `dyn`.
+fn is_unsized_type(ty: &Type) -> bool {
+    match *ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Paren(ref paren) => is_unsized_type(&paren.elem),
+        Type::Group(ref group) => is_unsized_type(&group.elem),
+        Type::Path(ref type_path) if type_path.qself.is_none() && type_path.path.leading_colon.is_none() => {
+            type_path.path.is_ident("str")
+        }
+        _ => false,
+    }
+}
+
+/// Generates the `FooDestructured` enum, and the `destructure()` method (or however it was
+/// named) and its aliases, for an enum.
+fn destructure_enum(input: &DeriveInput, data: &DataEnum, opts: &DestructureOpts) -> syn::Result<TokenStream> {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    if let Some(option) = opts.struct_only_option() {
+        errors.push(cx.error(
+            format!("#[destructure({})] only works on structs.", option),
+            &format!("remove {} from #[destructure(...)]", option),
+        ));
+    }
+    for variant in data.variants.iter() {
+        errors.check(check_pin_refs(input, &variant.fields));
+        for f in variant.fields.iter() {
+            let field_opts = field_opts(input, f);
+            if field_opts.skip || field_opts.forget || field_opts.flatten {
+                errors.push(cx.with_field(f).error(
+                    "#[destructure(skip)], #[destructure(forget)] and #[destructure(flatten)] only work on fields of structs.".to_owned(),
+                    "remove the attribute from the field",
+                ));
+            }
+        }
+    }
+    errors.finish()?;
+
+    let vis = &input.vis;
+    let name = &input.ident;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let destructured_name = Ident::new(&(name.to_string()+"Destructured"), Span::call_site());
+    let method = opts.method_name();
+    let unsafety = unsafety(opts);
+    let call_method = call_method(opts, &method);
+    let must_use = must_use_attr(input);
+
+    let variants = data.variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        if let Fields::Unit = variant.fields {
+            return quote!(#variant_ident);
+        }
+        let field_types = variant.fields.iter().map(|f| {
+            let ty = replace_self(input, &f.ty);
+            quote_spanned! {f.span()=>
+                #ty
+            }
+        });
+        match variant.fields {
+            Fields::Named(_) if opts.named => {
+                let field_names = variant.fields.iter().map(|f| &f.ident);
+                quote!(#variant_ident { #(#field_names: #field_types),* })
+            }
+            _ => quote!(#variant_ident(#(#field_types),*)),
+        }
+    });
+    let match_arms = data.variants.iter().map(|variant| {
+        let variant_ident = &variant.ident;
+        let members = field_members(&variant.fields);
+        let bindings: Vec<Ident> = members.iter().map(|member| {
+            match *member {
+                Member::Named(ref ident) => ident.clone(),
+                Member::Unnamed(ref index) => Ident::new(&format!("__{}", index.index), Span::call_site()),
+            }
+        }).collect();
+        let patterns = members.iter().zip(&bindings).map(|(member, binding)| {
+            match *member {
+                Member::Named(_) => quote!(ref #binding),
+                Member::Unnamed(_) => quote!(#member: ref #binding),
+            }
+        });
+        let reads = bindings.iter().map(|binding| quote!(#core::ptr::read(#binding)));
+        let value = match variant.fields {
+            Fields::Unit => quote!(#destructured_name::#variant_ident),
+            Fields::Named(_) if opts.named => {
+                let bindings = &bindings;
+                quote!(#destructured_name::#variant_ident { #(#bindings: #reads),* })
+            }
+            _ => quote!(#destructured_name::#variant_ident(#(#reads),*)),
+        };
+        quote! {
+            #name::#variant_ident { #(#patterns,)* } => #value
+        }
+    });
+    let body = consume_self(input, quote! {
+        match *self_ptr {
+            #(#match_arms,)*
+        }
+    });
+
+    let doc = opts.justification.as_ref().map(|justification| {
+        let doc = format!(" Skips `Drop` because: {}", justification);
+        quote!(#[doc = #doc])
+    });
+    let aliases = opts.aliases.iter().map(|alias| {
+        quote! {
+            #must_use
+            #inline
+            #unsafety fn #alias(self) -> #destructured_name #ty_generics {
+                #call_method
+            }
+        }
+    });
+    let drop_in_place = if opts.drop_in_place {
+        quote! {
+            #inline
+            unsafe fn drop_original_in_place(this: *mut Self) {
+                #core::ptr::drop_in_place(this)
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+    let try_into_variants = if opts.try_into_variants {
+        data.variants.iter().map(|variant| destructure_try_into_variant(input, variant, opts)).collect()
+    } else {
+        Vec::new()
+    };
+    let justification = justification_const(input, opts.justification.as_ref(), "destructure", "DESTRUCTURE_JUSTIFICATION");
+    let impl_trait = if opts.impl_trait {
+        destructure_trait_impl(input, quote!(#destructured_name #ty_generics), quote!(#name::#method(self)), opts.inline)
+    } else {
+        TokenStream::new()
+    };
+
+    Ok(quote! {
+        #vis enum #destructured_name #generics #where_clause {
+            #(#variants,)*
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #doc
+            #must_use
+            #inline
+            #unsafety fn #method(self) -> #destructured_name #ty_generics {
+                #body
+            }
+
+            #(#aliases)*
+            #drop_in_place
+            #(#try_into_variants)*
+        }
+
+        #impl_trait
+        #justification
+    })
+}
+
+/// Generates the impl of `derive_destructure_traits::Destructure` for
+/// `#[destructure(impl_trait)]`, which returns `fields_type` by evaluating `body`.
+fn destructure_trait_impl(input: &DeriveInput, fields_type: TokenStream, body: TokenStream, inline: Inline) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::derive_destructure_traits::Destructure for #name #ty_generics #where_clause {
+            type Fields = #fields_type;
+
+            #inline
+            fn destructure(self) -> Self::Fields {
+                #body
+            }
+        }
+    }
+}
+
+/// Generates the impl of `From<Foo>` for the returned tuple for `#[destructure(into_tuple)]`,
+/// which calls the generated method.
+fn destructure_into_tuple(input: &DeriveInput, returned: &[(&Field, Member)], opts: &DestructureOpts) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let method = opts.method_name();
+    let field_types = returned.iter().map(|&(f, _)| returned_type(input, f));
+    let members: Vec<&Member> = returned.iter().map(|(_, member)| member).collect();
+    let members = &members;
+    // The method may return a struct instead, which is turned into a tuple here.
+    let struct_path = match opts.into_struct {
+        Some(ref into_struct) => Some(struct_expr_path(into_struct)),
+        None if opts.returns_parts_struct(returned.len()) => {
+            let parts_name = Ident::new(&(name.to_string()+"Parts"), Span::call_site());
+            Some(quote!(#parts_name))
+        }
+        None => None,
+    };
+    let body = if let Some(struct_path) = struct_path {
+        quote! {
+            let #struct_path { #(#members,)* } = #name::#method(value);
+            (#(#members,)*)
+        }
+    } else {
+        quote!(#name::#method(value))
+    };
+
+    quote! {
+        impl #impl_generics #core::convert::From<#name #ty_generics> for (#(#field_types,)*) #where_clause {
+            #inline
+            fn from(value: #name #ty_generics) -> Self {
+                #body
+            }
+        }
+    }
+}
+
+/// Generates the `try_into_<variant>()` method for `#[destructure(try_into_variants)]`, which
+/// returns the fields of `variant` as a tuple, or `self` if it's a different variant.
+fn destructure_try_into_variant(input: &DeriveInput, variant: &Variant, opts: &DestructureOpts) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let unsafety = unsafety(opts);
+    let name = &input.ident;
+    let variant_ident = &variant.ident;
+    let method = Ident::new(&format!("try_into_{}", to_snake_case(&variant_ident.to_string())), Span::call_site());
+    let members = field_members(&variant.fields);
+    let bindings: Vec<Ident> = members.iter().map(|member| {
+        match *member {
+            Member::Named(ref ident) => ident.clone(),
+            Member::Unnamed(ref index) => Ident::new(&format!("__{}", index.index), Span::call_site()),
+        }
+    }).collect();
+    let patterns = members.iter().zip(&bindings).map(|(member, binding)| {
+        match *member {
+            Member::Named(_) => quote!(ref #binding),
+            Member::Unnamed(_) => quote!(#member: ref #binding),
+        }
+    });
+    let field_types = variant.fields.iter().map(|f| &f.ty);
+    let reads = bindings.iter().map(|binding| quote!(#core::ptr::read(#binding)));
+    let body = if bindings.is_empty() {
+        forget_self(input)
+    } else {
+        consume_self(input, quote! {
+            match *self_ptr {
+                #name::#variant_ident { #(#patterns,)* .. } => (#(#reads,)*),
+                _ => unreachable!(),
+            }
+        })
+    };
+    let ok_value = if bindings.is_empty() {
+        quote!(())
+    } else {
+        quote!(parts)
+    };
+
+    quote! {
+        #inline
+        #unsafety fn #method(self) -> #core::result::Result<(#(#field_types,)*), Self> {
+            match self {
+                #name::#variant_ident { .. } => {}
+                _ => return #core::result::Result::Err(self),
+            }
+            let parts = { #body };
+            #core::result::Result::Ok(#ok_value)
+        }
+    }
+}
+
+/// Generates the `destructure()` method (or however it was named) and its aliases.
+fn destructure_struct(input: &DeriveInput, fields: &Fields, opts: &DestructureOpts) -> syn::Result<TokenStream> {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let alloc = alloc_path(input);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let method = opts.method_name();
+    let returned = returned_fields(input, fields);
+
+    let field_types = returned.iter().map(|&(f, _)| returned_type(input, f));
+    let field_reads = returned.iter().map(|&(f, ref member)| read_field(input, f, member));
+    let parts_type = quote! {
+        (#(#field_types,)*)
+    };
+    let parts = quote! {
+        (#(#field_reads,)*)
+    };
+
+    let field_count = returned.len();
+    let is_empty = field_count == 0;
+    let return_type = if is_empty {
+        TokenStream::new()
+    } else {
+        quote!(-> #parts_type)
+    };
+    // The body of a method that returns a struct like `FooParts` instead of a tuple.
+    let struct_body = |struct_path: TokenStream| {
+        let members: Vec<&Member> = returned.iter().map(|(_, member)| member).collect();
+        let members = &members;
+        let consume = consume_fields(input, fields, opts);
+        quote! {
+            let (#(#members,)*) = { #consume };
+            #struct_path { #(#members,)* }
+        }
+    };
+    let parts_name = Ident::new(&(name.to_string()+"Parts"), Span::call_site());
+    // `FooParts` and `FooWithout<Field>` only have the generic parameters that the returned fields use.
+    let returned_generics = returned_generics(input, &returned, false);
+    let (_, returned_ty_generics, _) = returned_generics.split_for_impl();
+    let (method_return_type, body) = if let Some(ref into_struct) = opts.into_struct {
+        (quote!(-> #into_struct), struct_body(struct_expr_path(into_struct)))
+    } else if opts.returns_parts_struct(field_count) {
+        (quote!(-> #parts_name #returned_ty_generics), struct_body(quote!(#parts_name)))
+    } else if opts.defuse && field_count == 1 {
+        let payload_type = returned_type(input, returned[0].0);
+        let consume = consume_fields(input, fields, opts);
+        (quote!(-> #payload_type), quote! {
+            let (payload,) = { #consume };
+            payload
+        })
+    } else {
+        (return_type.clone(), consume_fields(input, fields, opts))
+    };
+    let constness = if opts.const_fn {
+        check_const_fn(input, fields, opts)?;
+        quote!(const)
+    } else {
+        TokenStream::new()
+    };
+
+    // The signatures and bodies of the generated methods.
+    let doc = opts.justification.as_ref().map(|justification| {
+        let doc = format!(" Skips `Drop` because: {}", justification);
+        quote!(#[doc = #doc])
+    });
+    let unsafety = unsafety(opts);
+    // Methods that return nothing have nothing to use.
+    let must_use = if is_empty {
+        TokenStream::new()
+    } else {
+        must_use_attr(input)
+    };
+    let mut methods = vec![(quote!(#doc #must_use #constness #unsafety fn #method(self) #method_return_type), body)];
+
+    for alias in &opts.aliases {
+        methods.push((quote!(#must_use #constness #unsafety fn #alias(self) #method_return_type), call_method(opts, &method)));
+    }
+
+    if opts.into_inner {
+        if field_count != 1 {
+            return Err(ErrorContext::new(input).error(
+                format!("#[destructure(into_inner)] only works on structs with exactly one field, but `{}` returns {}.", name, field_count),
+                "remove into_inner, or skip the other fields with #[destructure(skip)]",
+            ));
+        }
+        let inner_type = returned_type(input, returned[0].0);
+        let consume = consume_fields(input, fields, opts);
+        methods.push((
+            quote!(#must_use #constness #unsafety fn into_inner(self) -> #inner_type),
+            quote! {
+                let (inner,) = { #consume };
+                inner
+            },
+        ));
+    }
+
+    if opts.named {
+        methods.push((
+            quote!(#must_use #constness #unsafety fn destructure_named(self) -> #parts_name #returned_ty_generics),
+            struct_body(quote!(#parts_name)),
+        ));
+    }
+
+    if opts.rest {
+        let members: Vec<&Member> = returned.iter().map(|(_, member)| member).collect();
+        let members = &members;
+        for (i, &(f, ref taken)) in returned.iter().enumerate() {
+            let take_method = Ident::new(&format!("take_{}", quote!(#taken)), Span::call_site());
+            let ty = returned_type(input, f);
+            let rest_name = rest_name(input, taken);
+            let (others, rest_generics) = rest_fields(input, &returned, i);
+            let (_, rest_ty_generics, _) = rest_generics.split_for_impl();
+            let other_members = others.iter().map(|(_, member)| member);
+            let consume = consume_fields(input, fields, opts);
+            methods.push((
+                quote!(#must_use #unsafety fn #take_method(self) -> (#ty, #rest_name #rest_ty_generics)),
+                quote! {
+                    let (#(#members,)*) = { #consume };
+                    (#taken, #rest_name { #(#other_members,)* })
+                },
+            ));
+        }
+    }
+
+    if opts.with_fields {
+        let field_types = returned.iter().map(|&(f, _)| &f.ty);
+        let bindings: Vec<Ident> = (0..field_count).map(|i| Ident::new(&format!("__field_{}", i), Span::call_site())).collect();
+        let bindings = &bindings;
+        let consume = consume_fields(input, fields, opts);
+        methods.push((
+            quote!(#unsafety fn with_fields<__R>(self, f: impl #core::ops::FnOnce(#(#field_types),*) -> __R) -> __R),
+            quote! {
+                let (#(#bindings,)*) = { #consume };
+                f(#(#bindings),*)
+            },
+        ));
+    }
+
+    if opts.with_methods {
+        if let Fields::Unnamed(_) = *fields {
+            return Err(ErrorContext::new(input).error(
+                "#[destructure(with_methods)] doesn't work on tuple structs, because the with_<field> methods are named after the fields.".to_owned(),
+                "remove with_methods from #[destructure(...)]",
+            ));
+        }
+        for f in fields.iter() {
+            let ident = &f.ident;
+            let ty = &f.ty;
+            let with_method = Ident::new(&format!("with_{}", quote!(#ident)), Span::call_site());
+            methods.push((
+                quote!(fn #with_method(mut self, #ident: #ty) -> Self),
+                quote! {
+                    self.#ident = #ident;
+                    self
+                },
+            ));
+        }
+    }
+
+    if opts.replace_methods {
+        if let Fields::Unnamed(_) = *fields {
+            return Err(ErrorContext::new(input).error(
+                "#[destructure(replace_methods)] doesn't work on tuple structs, because the replace_<field> methods are named after the fields.".to_owned(),
+                "remove replace_methods from #[destructure(...)]",
+            ));
+        }
+        for f in fields.iter() {
+            let ident = &f.ident;
+            let ty = &f.ty;
+            let replace_method = Ident::new(&format!("replace_{}", quote!(#ident)), Span::call_site());
+            methods.push((
+                quote!(fn #replace_method(&mut self, #ident: #ty) -> #ty),
+                quote!(#core::mem::replace(&mut self.#ident, #ident)),
+            ));
+        }
+    }
+
+    if opts.from_parts {
+        if returned.len() != fields.iter().count() {
+            return Err(ErrorContext::new(input).error(
+                "#[destructure(from_parts)] doesn't work with #[destructure(skip)] or #[destructure(forget)] fields, because they're not in the tuple.".to_owned(),
+                "remove from_parts, or stop skipping the fields",
+            ));
+        }
+        let bindings = fields.iter().enumerate().map(|(i, f)| {
+            match f.ident {
+                Some(ref ident) => ident.clone(),
+                None => Ident::new(&format!("__{}", i), Span::call_site()),
+            }
+        });
+        let field_bindings = field_bindings(fields);
+        methods.push((
+            quote!(#constness fn from_parts(parts: #parts_type) -> Self),
+            quote! {
+                let (#(#bindings,)*) = parts;
+                #name { #(#field_bindings,)* }
+            },
+        ));
+    }
+
+    if opts.field_by_name {
+        let field_names = returned.iter().map(|(_, member)| quote!(#member).to_string());
+        let consume = consume_fields(input, fields, opts);
+        let arms = field_names.enumerate().map(|(i, field_name)| {
+            let patterns = (0..field_count).map(|j| {
+                if i == j {
+                    quote!(field)
+                } else {
+                    quote!(_)
+                }
+            });
+            quote! {
+                #field_name => {
+                    let (#(#patterns,)*) = { #consume };
+                    #core::result::Result::Ok(#alloc::boxed::Box::new(field))
+                }
+            }
+        });
+        let field_types = returned.iter().map(|&(f, _)| &f.ty);
+        let sig = quote! {
+            #unsafety fn into_field_by_name(self, name: &str) -> #core::result::Result<#alloc::boxed::Box<dyn #core::any::Any>, Self>
+                where #(#field_types: 'static,)*
+        };
+        let body = quote! {
+            match name {
+                #(#arms)*
+                _ => #core::result::Result::Err(self),
+            }
+        };
+        methods.push((sig, body));
+    }
+
+    if opts.fields_any_mut {
+        let members = field_members(fields);
+        let field_types = fields.iter().map(|f| &f.ty);
+        let all_field_count = members.len();
+        let sig = quote! {
+            fn fields_any_mut(&mut self) -> [&mut dyn #core::any::Any; #all_field_count]
+                where #(#field_types: 'static,)*
+        };
+        let any_muts = members.iter().map(|member| quote!(&mut self.#member as &mut dyn #core::any::Any));
+        let body = quote! {
+            [#(#any_muts,)*]
+        };
+        methods.push((sig, body));
+    }
+
+    if opts.as_parts {
+        let field_types = returned.iter().map(|&(f, _)| &f.ty);
+        let members = returned.iter().map(|(_, member)| member);
+        let (return_type, body) = if is_empty {
+            (TokenStream::new(), TokenStream::new())
+        } else {
+            (quote!(-> (#(&#field_types,)*)), quote!((#(&self.#members,)*)))
+        };
+        methods.push((quote!(fn as_parts(&self) #return_type), body));
+    }
+
+    if opts.as_parts_mut {
+        let field_types = returned.iter().map(|&(f, _)| &f.ty);
+        let members = returned.iter().map(|(_, member)| member);
+        let (return_type, body) = if is_empty {
+            (TokenStream::new(), TokenStream::new())
+        } else {
+            (quote!(-> (#(&mut #field_types,)*)), quote!((#(&mut self.#members,)*)))
+        };
+        methods.push((quote!(fn as_parts_mut(&mut self) #return_type), body));
+    }
+
+    if opts.cloned {
+        let cloned_method = Ident::new(&format!("{}_cloned", method), Span::call_site());
+        let bounds = returned.iter().map(|&(f, _)| {
+            let ty = &f.ty;
+            quote!(#ty: #core::clone::Clone)
+        });
+        let clones = returned.iter().map(|(_, member)| quote!(#core::clone::Clone::clone(&self.#member)));
+        let body = if is_empty {
+            TokenStream::new()
+        } else {
+            quote!((#(#clones,)*))
+        };
+        let sig = quote! {
+            fn #cloned_method(&self) #return_type
+                where #(#bounds,)*
+        };
+        methods.push((sig, body));
+    }
+
+    if opts.take_parts {
+        let bounds = returned.iter().map(|&(f, _)| {
+            let ty = &f.ty;
+            quote!(#ty: #core::default::Default)
+        });
+        let takes = returned.iter().map(|(_, member)| {
+            quote!(#core::mem::replace(&mut self.#member, #core::default::Default::default()))
+        });
+        let body = if is_empty {
+            TokenStream::new()
+        } else {
+            quote!((#(#takes,)*))
+        };
+        let sig = quote! {
+            fn take_parts(&mut self) #return_type
+                where #(#bounds,)*
+        };
+        methods.push((sig, body));
+    }
+
+    if opts.drop_in_place {
+        methods.push((
+            quote!(unsafe fn drop_original_in_place(this: *mut Self)),
+            quote!(#core::ptr::drop_in_place(this)),
+        ));
+    }
+
+    if opts.with_shell {
+        let with_shell_method = Ident::new(&format!("{}_with_shell", method), Span::call_site());
+        let skipped: Vec<Member> = fields.iter().zip(field_members(fields)).filter(|&(f, _)| {
+            field_opts(input, f).skip
+        }).map(|(_, member)| member).collect();
+        // The skipped fields are dropped in place, so that they're not in the shell anymore.
+        let drop_skipped = if skipped.is_empty() {
+            TokenStream::new()
+        } else {
+            let drops = skipped.iter().map(|member| {
+                if is_packed(input) {
+                    quote!(#core::mem::drop(#core::ptr::read_unaligned(#core::ptr::addr_of!((*self_ptr).#member))))
+                } else {
+                    quote!(#core::ptr::drop_in_place(&mut (*self_ptr).#member))
+                }
+            });
+            quote! {
+                let mut maybe_uninit = maybe_uninit;
+                unsafe {
+                    let self_ptr = maybe_uninit.as_mut_ptr();
+                    #(#drops;)*
+                }
+            }
+        };
+        // With `#[destructure(zeroize)]`, the returned shell is the storage that gets wiped.
+        let wipe = if opts.zeroize {
+            let wipe = wipe_shell(input, quote!(maybe_uninit.as_mut_ptr() as *mut u8));
+            quote! {
+                let mut maybe_uninit = maybe_uninit;
+                #wipe
+            }
+        } else {
+            TokenStream::new()
+        };
+        let (before, value) = before_hook(input, opts);
+        let body = if is_empty {
+            quote! {
+                #before
+                let maybe_uninit = #core::mem::MaybeUninit::new(#value);
+                #drop_skipped
+                #wipe
+                ((), maybe_uninit)
+            }
+        } else {
+            quote! {
+                #before
+                let maybe_uninit = #core::mem::MaybeUninit::new(#value);
+                let parts = unsafe {
+                    let self_ptr = maybe_uninit.as_ptr();
+                    #parts
+                };
+                #drop_skipped
+                #wipe
+                (parts, maybe_uninit)
+            }
+        };
+        let must_use = must_use_attr(input);
+        methods.push((quote!(#must_use #unsafety fn #with_shell_method(self) -> (#parts_type, #core::mem::MaybeUninit<Self>)), body));
+    }
+
+    if opts.boxed {
+        let boxed_method = Ident::new(&format!("{}_boxed", method), Span::call_site());
+        let body = consume_boxed_fields(input, fields, opts);
+        methods.push((quote!(#must_use #unsafety fn #boxed_method(self: #alloc::boxed::Box<Self>) #return_type), body));
+    }
+
+    // With `#[destructure(instrument)]`, the events report where the methods were called from.
+    let track_caller = if opts.instrument.is_some() {
+        quote!(#[track_caller])
+    } else {
+        TokenStream::new()
+    };
+    let guard = if opts.guard {
+        destructure_guard(input, opts, &method_return_type)
+    } else {
+        TokenStream::new()
+    };
+
+    let method_impls = methods.iter().map(|(sig, body)| {
+        quote! {
+            #inline
+            #track_caller
+            #sig {
+                #body
+            }
+        }
+    });
+
+    if opts.ext_trait {
+        let vis = &input.vis;
+        let trait_name = Ident::new(&(name.to_string()+"DestructureExt"), Span::call_site());
+        let generics = &input.generics;
+        let sigs = methods.iter().map(|(sig, _)| sig);
+        Ok(quote! {
+            #vis trait #trait_name #generics #where_clause {
+                #(#sigs;)*
+            }
+
+            impl #impl_generics #trait_name #ty_generics for #name #ty_generics #where_clause {
+                #(#method_impls)*
+            }
+
+            #guard
+        })
+    } else {
+        Ok(quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                #(#method_impls)*
+            }
+
+            #guard
+        })
+    }
+}
+
+/// Generates the `FooGuard` wrapper for `#[destructure(guard)]`, which holds a `Foo` until it's
+/// either taken apart with `into_parts()`, unwrapped with `into_original()`, or dropped along
+/// with the guard, which runs the destructor of `Foo` as usual.
+fn destructure_guard(input: &DeriveInput, opts: &DestructureOpts, return_type: &TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let vis = &input.vis;
+    let name = &input.ident;
+    let guard_name = Ident::new(&(name.to_string()+"Guard"), Span::call_site());
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let method = opts.method_name();
+    let unsafety = unsafety(opts);
+    let into_parts = if opts.unsafe_fn {
+        quote!(unsafe { self.0.#method() })
+    } else {
+        quote!(self.0.#method())
+    };
+    let must_use = must_use_attr(input);
+
+    quote! {
+        #vis struct #guard_name #generics (#name #ty_generics) #where_clause;
+
+        impl #impl_generics #guard_name #ty_generics #where_clause {
+            #inline
+            fn new(original: #name #ty_generics) -> Self {
+                #guard_name(original)
+            }
+
+            #must_use
+            #inline
+            #unsafety fn into_parts(self) #return_type {
+                #into_parts
+            }
+
+            #inline
+            fn into_original(self) -> #name #ty_generics {
+                self.0
+            }
+        }
+
+        impl #impl_generics #core::ops::Deref for #guard_name #ty_generics #where_clause {
+            type Target = #name #ty_generics;
+
+            #inline
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl #impl_generics #core::ops::DerefMut for #guard_name #ty_generics #where_clause {
+            #inline
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+    }
+}
+
+/// The `#[must_use]` attribute of the generated methods and types that take a value apart without
+/// running its destructor, because dropping the result right away is almost certainly a mistake.
+fn must_use_attr(input: &DeriveInput) -> TokenStream {
+    let message = format!("the destructor of `{}` doesn't run, so ignoring the result drops its fields without cleaning up", input.ident);
+    quote!(#[must_use = #message])
+}
+
+/// `unsafe` for `#[destructure(unsafe_fn)]`, to put in the signatures of the generated methods
+/// that bypass `Drop`.
+fn unsafety(opts: &DestructureOpts) -> TokenStream {
+    if opts.unsafe_fn {
+        quote!(unsafe)
+    } else {
+        TokenStream::new()
+    }
+}
+
+/// Generates the body of an alias of the generated method, which calls `method`.
+fn call_method(opts: &DestructureOpts, method: &Ident) -> TokenStream {
+    if opts.unsafe_fn {
+        quote!(unsafe { self.#method() })
+    } else {
+        quote!(self.#method())
+    }
+}
+
+/// Checks that the methods generated for `#[destructure(const_fn)]` can be `const fn`s: they
+/// need `ptr::read` in constants, and can't run the destructors of skipped fields.
+fn check_const_fn(input: &DeriveInput, fields: &Fields, opts: &DestructureOpts) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    if opts.ext_trait {
+        errors.push(cx.error(
+            "#[destructure(const_fn)] can't be combined with ext_trait, because trait methods can't be const.".to_owned(),
+            "remove either const_fn or ext_trait",
+        ));
+    }
+    for f in fields.iter() {
+        let field_opts = field_opts(input, f);
+        if field_opts.skip && !(is_copy_type(&f.ty) || field_opts.copy) {
+            errors.push(cx.with_field(f).error(
+                "#[destructure(skip)] fields can't be dropped in a const fn.".to_owned(),
+                "use #[destructure(forget)] instead of skip, or remove const_fn",
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// The path of the crate that the generated code takes `ptr`, `mem` and the other modules of the
+/// standard library from: the `crate_path` option if it's set, and `::core` otherwise, which
+/// works with and without `std`. Errors in the options have already been reported by the
+/// derive's entry point.
+fn core_path(input: &DeriveInput) -> TokenStream {
+    if let Ok(Some(path)) = crate_path(ErrorContext::new(input), &input.attrs) {
+        quote!(#path)
+    } else {
+        quote!(::core)
+    }
+}
+
+/// The path of the crate that the generated code takes `Box`, `Cow` and `ToOwned` from: the
+/// `crate_path` option if it's set, `::alloc` with the `no_std` option, and `::std` otherwise.
+/// Like in `core_path`, errors in the options have already been reported.
+fn alloc_path(input: &DeriveInput) -> TokenStream {
+    let cx = ErrorContext::new(input);
+    if let Ok(Some(path)) = crate_path(cx, &input.attrs) {
+        quote!(#path)
+    } else if no_std(cx, &input.attrs).unwrap_or(false) {
+        quote!(::alloc)
+    } else {
+        quote!(::std)
+    }
+}
+
+/// Generates a function body that evaluates `parts` and returns it, without running the
+/// destructor of `self`. `parts` can use `self_ptr: *const Self` to read the fields of `self`,
+/// which must all be moved out with `ptr::read`.
+fn consume_self(input: &DeriveInput, parts: TokenStream) -> TokenStream {
+    consume_value(input, &quote!(self), parts)
+}
+
+/// Like `consume_self`, but for `value: Self` instead of `self`.
+fn consume_value(input: &DeriveInput, value: &TokenStream, parts: TokenStream) -> TokenStream {
+    // The fields are read straight out of the `ManuallyDrop`, so `self` is never copied as a
+    // whole. It's `#[repr(transparent)]`, so the pointer cast is fine, and unlike `Deref`, it
+    // also works in a `const fn`.
+    let core = core_path(input);
+    let shell_ptr = shell_ptr(input);
+    quote! {
+        let this = #core::mem::ManuallyDrop::new(#value);
+        unsafe {
+            let self_ptr = #shell_ptr;
+            #parts
+        }
+    }
+}
+
+/// Generates a `*const Self` that points into `this: ManuallyDrop<Self>`. It's made with
+/// `addr_of!`, so that not even a reference to the `ManuallyDrop` is created.
+fn shell_ptr(input: &DeriveInput) -> TokenStream {
+    let core = core_path(input);
+    quote!(#core::ptr::addr_of!(this) as *const Self)
+}
+
+/// Like `consume_value`, but for `#[destructure(zeroize)]`: once the fields are read, the storage
+/// of `value` is overwritten with zeros.
+fn consume_value_zeroized(input: &DeriveInput, value: &TokenStream, parts: TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let wipe = wipe_shell(input, quote!(&mut this as *mut #core::mem::ManuallyDrop<Self> as *mut u8));
+    let shell_ptr = shell_ptr(input);
+    quote! {
+        let mut this = #core::mem::ManuallyDrop::new(#value);
+        let parts = unsafe {
+            let self_ptr = #shell_ptr;
+            #parts
+        };
+        #wipe
+        parts
+    }
+}
+
+/// Like `forget_value`, but for `#[destructure(zeroize)]`: the storage of `value` is overwritten
+/// with zeros.
+fn forget_value_zeroized(input: &DeriveInput, value: &TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let wipe = wipe_shell(input, quote!(&mut this as *mut #core::mem::ManuallyDrop<Self> as *mut u8));
+    quote! {
+        let mut this = #core::mem::ManuallyDrop::new(#value);
+        #wipe
+    }
+}
+
+/// Generates code that overwrites the storage of a `Self` at `shell`, a `*mut u8`, with zeros,
+/// by calling `::zeroize::Zeroize` on its bytes. They're `MaybeUninit<u8>`s, because the padding
+/// and the fields that were moved out don't have to be initialized. The storage isn't used as a
+/// `Self` afterwards, so it doesn't matter that the zeros may not be a valid `Self`.
+fn wipe_shell(input: &DeriveInput, shell: TokenStream) -> TokenStream {
+    let core = core_path(input);
+    quote! {
+        ::zeroize::Zeroize::zeroize(unsafe {
+            #core::slice::from_raw_parts_mut(#shell as *mut #core::mem::MaybeUninit<u8>, #core::mem::size_of::<Self>())
+        });
+    }
+}
+
+/// Checks that `#[destructure(zeroize)]` can be used: `Zeroize::zeroize` can't be called in a
+/// const fn.
+fn check_zeroize(input: &DeriveInput, opts: &DestructureOpts) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    if opts.const_fn {
+        errors.push(cx.error(
+            "#[destructure(zeroize)] can't be combined with const_fn, because Zeroize::zeroize isn't a const fn.".to_owned(),
+            "remove either zeroize or const_fn",
+        ));
+    }
+    errors.finish()
+}
+
+/// Returns whether the struct has a `#[repr(packed)]` attribute, so that its fields may not be
+/// aligned.
+fn is_packed(input: &DeriveInput) -> bool {
+    repr_items(input).iter().any(|item| item.path().is_ident("packed"))
+}
+
+/// The items of the `#[repr(...)]` attributes of the type, like `C` and `packed(2)`.
+fn repr_items(input: &DeriveInput) -> Vec<Meta> {
+    input.attrs.iter().filter(|attr| attr.path().is_ident("repr")).flat_map(|attr| {
+        attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated).map_or_else(|_| Vec::new(), |items| items.into_iter().collect())
+    }).collect()
+}
+
+/// Generates an expression that moves the field `member` out of `self_ptr`. The field is read
+/// through a pointer made with `addr_of!`, without creating a reference. The
+/// fields of `#[repr(packed)]` structs may not be aligned, so they're read with `read_unaligned`.
+fn read_field<M: ToTokens>(input: &DeriveInput, f: &Field, member: M) -> TokenStream {
+    let core = core_path(input);
+    if is_packed(input) {
+        quote_spanned! {f.span()=>
+            #core::ptr::read_unaligned(#core::ptr::addr_of!((*self_ptr).#member))
+        }
+    } else {
+        quote_spanned! {f.span()=>
+            #core::ptr::read(#core::ptr::addr_of!((*self_ptr).#member))
+        }
+    }
+}
+
+/// Generates code that moves the returned fields out of `self` as a tuple, without running its
+/// destructor. Fields of `Copy` types are copied without `unsafe`, so that only the other
+/// fields need `ptr::read`. Skipped fields are dropped after `self` is gone, and forgotten
+/// fields are left behind. The `before` hook runs first, if there is one.
+fn consume_fields(input: &DeriveInput, fields: &Fields, opts: &DestructureOpts) -> TokenStream {
+    let (before, value) = before_hook(input, opts);
+    let consume = if opts.zeroize {
+        consume_fields_with(input, fields, &value, |parts| consume_value_zeroized(input, &value, parts), forget_value_zeroized(input, &value))
+    } else {
+        consume_fields_with(input, fields, &value, |parts| consume_value(input, &value, parts), forget_value(input, &value))
+    };
+    quote! {
+        #before
+        #consume
+    }
+}
+
+/// Generates the call to the hook of `#[destructure(before = "...")]`, if there is one, and
+/// returns it along with the value to take apart afterwards: `self`, or the mutable binding that
+/// `self` was moved into for the hook. The event of `#[destructure(instrument)]` comes first.
+fn before_hook(input: &DeriveInput, opts: &DestructureOpts) -> (TokenStream, TokenStream) {
+    let event = match opts.instrument {
+        Some(backend) => instrument_event(input, backend),
+        None => TokenStream::new(),
+    };
+    match opts.before {
+        Some(ref before) => (quote!(#event let mut hooked = self; #before(&mut hooked);), quote!(hooked)),
+        None => (event, quote!(self)),
+    }
+}
+
+/// Generates the event of `#[destructure(instrument)]`, which says that `Drop` was bypassed and
+/// where the generated method was called from, to `log` or `tracing`.
+fn instrument_event(input: &DeriveInput, backend: Instrument) -> TokenStream {
+    let core = core_path(input);
+    let type_name = input.ident.to_string();
+    match backend {
+        Instrument::Tracing => quote! {
+            ::tracing::debug!(type_name = #type_name, location = %#core::panic::Location::caller(), "bypassed Drop");
+        },
+        Instrument::Log => quote! {
+            ::log::debug!("bypassed Drop of `{}` at {}", #type_name, #core::panic::Location::caller());
+        },
+    }
+}
+
+/// Checks that `#[destructure(instrument)]` can be used: the caller's location needs
+/// `#[track_caller]`, which doesn't work in a const fn.
+fn check_instrument(input: &DeriveInput, opts: &DestructureOpts) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    if opts.const_fn {
+        errors.push(cx.error(
+            "#[destructure(instrument)] can't be combined with const_fn, because events can't be emitted in a const fn.".to_owned(),
+            "remove either instrument or const_fn",
+        ));
+    }
+    errors.finish()
+}
+
+/// Like `consume_fields`, but for `self: Box<Self>`. The fields are read straight out of the
+/// heap, and then the allocation is freed without running the destructor.
+fn consume_boxed_fields(input: &DeriveInput, fields: &Fields, opts: &DestructureOpts) -> TokenStream {
+    let core = core_path(input);
+    let alloc = alloc_path(input);
+    let (before, value) = before_hook(input, opts);
+    let free = quote! {
+        #core::mem::drop(#alloc::boxed::Box::from_raw(self_ptr as *mut #core::mem::ManuallyDrop<Self>))
+    };
+    // With `#[destructure(zeroize)]`, the heap allocation is wiped before it's freed.
+    let wipe = if opts.zeroize {
+        wipe_shell(input, quote!(self_ptr as *mut u8))
+    } else {
+        TokenStream::new()
+    };
+    let forget = quote! {
+        let self_ptr = #alloc::boxed::Box::into_raw(#value);
+        #wipe
+        unsafe {
+            #free;
+        }
+    };
+    let consume = consume_fields_with(input, fields, &value, |parts| quote! {
+        let self_ptr = #alloc::boxed::Box::into_raw(#value);
+        let parts = unsafe { #parts };
+        #wipe
+        unsafe {
+            #free;
+        }
+        parts
+    }, forget);
+    quote! {
+        #before
+        #consume
+    }
+}
+
+/// The code of `consume_fields` for `value`, where `consume` turns the reads of the fields into
+/// code that consumes `value` like `consume_value`, and `forget` gets rid of it like
+/// `forget_value`.
+fn consume_fields_with<F: Fn(TokenStream) -> TokenStream>(input: &DeriveInput, fields: &Fields, value: &TokenStream, consume: F, forget: TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let mut copies = Vec::new();
+    let mut reads = Vec::new();
+    let mut read_bindings = Vec::new();
+    let mut bindings = Vec::new();
+    let mut drops = Vec::new();
+    let mut flattened = false;
+    for (i, (f, member)) in fields.iter().zip(field_members(fields)).enumerate() {
+        let field_opts = field_opts(input, f);
+        let binding = Ident::new(&format!("__part_{}", i), Span::call_site());
+        let is_copy = is_copy_type(&f.ty) || field_opts.copy;
+        // Fields that are forgotten are simply never read, so they're gone along with `self`.
+        if field_opts.forget || (field_opts.skip && is_copy) {
+            continue;
+        }
+        if is_copy {
+            copies.push(quote_spanned! {f.span()=>
+                let #binding = #value.#member;
+            });
+        } else {
+            reads.push(read_field(input, f, &member));
+            read_bindings.push(binding.clone());
+        }
+        if field_opts.skip {
+            drops.push(quote! {
+                #core::mem::drop(#binding);
+            });
+        } else if field_opts.flatten {
+            flattened = true;
+            bindings.push(quote_spanned! {f.span()=>
+                ::derive_destructure_traits::Destructure::destructure(#binding)
+            });
+        } else {
+            bindings.push(quote!(#binding));
+        }
+    }
+
+    if copies.is_empty() && drops.is_empty() && !flattened {
+        if reads.is_empty() {
+            return forget;
+        }
+        return consume(quote!((#(#reads,)*)));
+    }
+    let consume = if reads.is_empty() {
+        forget
+    } else {
+        let consume = consume(quote!((#(#reads,)*)));
+        quote! {
+            let (#(#read_bindings,)*) = { #consume };
+        }
+    };
+    let parts = if bindings.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!((#(#bindings,)*))
+    };
+    quote! {
+        #(#copies)*
+        #consume
+        #(#drops)*
+        #parts
+    }
+}
+
+/// Returns the path of the struct `path` without generic arguments, like `FooData` for
+/// `FooData<T>`, for struct expressions and patterns, where the arguments are inferred.
+fn struct_expr_path(path: &Path) -> TokenStream {
+    let mut path = path.clone();
+    if let Some(last) = path.segments.last_mut() {
+        last.arguments = PathArguments::None;
+    }
+    quote!(#path)
+}
+
+/// Returns the type that `destructure()` returns for `f`: the type of the field, or for a
+/// `#[destructure(flatten)]` field, the type of its own fields. `Self` is replaced, so that the
+/// type can also be used outside of impls, like in `FooParts`.
+fn returned_type(input: &DeriveInput, f: &Field) -> TokenStream {
+    let ty = replace_self(input, &f.ty);
+    if field_opts(input, f).flatten {
+        quote_spanned! {f.span()=>
+            <#ty as ::derive_destructure_traits::Destructure>::Fields
+        }
+    } else {
+        quote_spanned! {f.span()=>
+            #ty
+        }
+    }
+}
+
+/// Returns the generics of the type for the types that only hold the returned fields, like
+/// `FooParts`, without the parameters that none of those fields use, because unused parameters
+/// aren't allowed. Type aliases like `FooFields` do allow unused lifetimes, so `keep_lifetimes`
+/// keeps them all. The bounds that mention the removed parameters are removed as well.
+fn returned_generics(input: &DeriveInput, returned: &[(&Field, Member)], keep_lifetimes: bool) -> Generics {
+    let mut used = Vec::new();
+    for &(f, _) in returned {
+        generic_names(returned_type(input, f), &mut used);
+    }
+    let mut generics = input.generics.clone();
+    let unused: Vec<String> = generics.params.iter().filter(|param| {
+        !(keep_lifetimes && matches!(**param, GenericParam::Lifetime(_)))
+    }).map(generic_param_name).filter(|name| !used.contains(name)).collect();
+    if unused.is_empty() {
+        return generics;
+    }
+    let mentions_unused = |tokens: TokenStream| {
+        let mut names = Vec::new();
+        generic_names(tokens, &mut names);
+        names.iter().any(|name| unused.contains(name))
+    };
+
+    generics.params = generics.params.into_iter().filter(|param| !unused.contains(&generic_param_name(param))).collect();
+    for param in generics.params.iter_mut() {
+        match *param {
+            GenericParam::Type(ref mut param) => {
+                param.bounds = param.bounds.clone().into_iter().filter(|bound| !mentions_unused(bound.into_token_stream())).collect();
+                if param.default.as_ref().is_some_and(|default| mentions_unused(default.1.to_token_stream())) {
+                    param.default = None;
+                }
+            }
+            GenericParam::Lifetime(ref mut def) => {
+                def.bounds = def.bounds.clone().into_iter().filter(|bound| !mentions_unused(bound.into_token_stream())).collect();
+            }
+            GenericParam::Const(ref mut param) => {
+                if param.default.as_ref().is_some_and(|default| mentions_unused(default.1.to_token_stream())) {
+                    param.default = None;
+                }
+            }
+        }
+    }
+    if let Some(ref mut where_clause) = generics.where_clause {
+        where_clause.predicates = where_clause.predicates.clone().into_iter().filter(|predicate| !mentions_unused(predicate.into_token_stream())).collect();
+    }
+    generics
+}
+
+/// The name of a generic parameter as it appears in types, like `T`, `'a` or `N`.
+fn generic_param_name(param: &GenericParam) -> String {
+    match *param {
+        GenericParam::Type(ref param) => param.ident.to_string(),
+        GenericParam::Lifetime(ref def) => def.lifetime.to_string(),
+        GenericParam::Const(ref param) => param.ident.to_string(),
+    }
+}
+
+/// Adds the names and lifetimes in `tokens` to `names`, in the format of `generic_param_name`.
+fn generic_names(tokens: TokenStream, names: &mut Vec<String>) {
+    let mut after_quote = false;
+    for token in tokens {
+        match token {
+            TokenTree::Ident(ref ident) if after_quote => names.push(format!("'{}", ident)),
+            TokenTree::Ident(ref ident) => names.push(ident.to_string()),
+            TokenTree::Group(ref group) => generic_names(group.stream(), names),
+            _ => {}
+        }
+        after_quote = match token {
+            TokenTree::Punct(ref punct) => punct.as_char() == '\'',
+            _ => false,
+        };
+    }
+}
+
+/// Returns the fields that `destructure()` returns, which are all fields except the ones marked
+/// `#[destructure(skip)]` or `#[destructure(forget)]`, along with how to access them.
+fn returned_fields<'a>(input: &DeriveInput, fields: &'a Fields) -> Vec<(&'a Field, Member)> {
+    fields.iter().zip(field_members(fields)).filter(|&(f, _)| {
+        let field_opts = field_opts(input, f);
+        !field_opts.skip && !field_opts.forget
+    }).collect()
+}
+
+/// Whether `ty` is obviously `Copy`: a primitive type, a shared reference, a raw pointer, or a
+/// tuple or array of those.
+fn is_copy_type(ty: &Type) -> bool {
+    match *ty {
+        Type::Reference(ref reference) => reference.mutability.is_none(),
+        Type::Ptr(_) => true,
+        Type::Array(ref array) => is_copy_type(&array.elem),
+        Type::Tuple(ref tuple) => tuple.elems.iter().all(is_copy_type),
+        Type::Paren(ref paren) => is_copy_type(&paren.elem),
+        Type::Path(ref type_path) if type_path.qself.is_none() && type_path.path.leading_colon.is_none() => {
+            let segments = &type_path.path.segments;
+            if segments.len() != 1 {
+                return false;
+            }
+            let segment = &segments[0];
+            if !segment.arguments.is_empty() {
+                return false;
+            }
+            const PRIMITIVES: &[&str] = &[
+                "bool", "char", "f32", "f64", "i8", "i16", "i32", "i64", "i128", "isize",
+                "u8", "u16", "u32", "u64", "u128", "usize",
+            ];
+            PRIMITIVES.iter().any(|primitive| segment.ident == primitive)
+        }
+        _ => false,
+    }
+}
+
+/// Generates code that gets rid of `self` without running its destructor.
+fn forget_self(input: &DeriveInput) -> TokenStream {
+    forget_value(input, &quote!(self))
+}
+
+/// Like `forget_self`, but for `value: Self` instead of `self`.
+fn forget_value(input: &DeriveInput, value: &TokenStream) -> TokenStream {
+    let core = core_path(input);
+    quote! {
+        let _ = #core::mem::ManuallyDrop::new(#value);
+    }
+}
+
+/// Whether `ty` is `Pin<&T>` or `Pin<&mut T>`. Pinned owning pointers like `Pin<Box<T>>` can be
+/// moved around freely, but pinned references need more care.
+fn is_pin_ref(ty: &Type) -> bool {
+    let path = match *ty {
+        Type::Path(ref type_path) => &type_path.path,
+        _ => return false,
+    };
+    let last = match path.segments.last() {
+        Some(last) => last,
+        None => return false,
+    };
+    if last.ident != "Pin" {
+        return false;
+    }
+    match last.arguments {
+        PathArguments::AngleBracketed(ref args) => args.args.iter().any(|arg| {
+            matches!(*arg, GenericArgument::Type(Type::Reference(_)))
+        }),
+        _ => false,
+    }
+}
+
+/// Returns `x` for a named field `x` and `0: __0` for a field of a tuple struct. This can be used
+/// both in patterns, to bind the fields to local variables, and in struct expressions, to
+/// initialize the fields with those variables.
+fn field_bindings(fields: &Fields) -> Vec<TokenStream> {
+    fields.iter().enumerate().map(|(i, f)| {
+        match f.ident {
+            Some(ref ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                let binding = Ident::new(&format!("__{}", i), Span::call_site());
+                quote!(#index: #binding)
+            }
+        }
+    }).collect()
+}
+
+/// Returns the members (`self.x` or `self.0`) with which the fields can be accessed.
+fn field_members(fields: &Fields) -> Vec<Member> {
+    fields.iter().enumerate().map(|(i, f)| {
+        match f.ident {
+            Some(ref ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        }
+    }).collect()
+}
+
+/// Generates the `FIELD_SIZES` and `FIELD_ALIGNS` constants for `#[destructure(field_layout)]`.
+fn destructure_field_layout(input: &DeriveInput, returned: &[(&Field, Member)]) -> TokenStream {
+    let core = core_path(input);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let sizes = returned.iter().map(|&(f, _)| {
+        let ty = &f.ty;
+        quote_spanned! {f.span()=>
+            #core::mem::size_of::<#ty>()
+        }
+    });
+    let aligns = returned.iter().map(|&(f, _)| {
+        let ty = &f.ty;
+        quote_spanned! {f.span()=>
+            #core::mem::align_of::<#ty>()
+        }
+    });
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The sizes of the fields returned by `destructure()`, in order.
+            const FIELD_SIZES: &'static [usize] = &[#(#sizes,)*];
+            /// The alignments of the fields returned by `destructure()`, in order.
+            const FIELD_ALIGNS: &'static [usize] = &[#(#aligns,)*];
+        }
+    }
+}
+
+/// Generates the `FIELD_COUNT`, `FIELD_NAMES` and `NEEDS_DROP` constants for
+/// `#[destructure(field_info)]`.
+fn destructure_field_info(input: &DeriveInput, returned: &[(&Field, Member)]) -> TokenStream {
+    let core = core_path(input);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let field_count = returned.len();
+    let field_names = returned.iter().map(|(_, member)| quote!(#member).to_string());
+    let needs_drop = returned.iter().map(|&(f, _)| {
+        let ty = &f.ty;
+        quote_spanned! {f.span()=>
+            #core::mem::needs_drop::<#ty>()
+        }
+    });
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The number of fields returned by `destructure()`.
+            const FIELD_COUNT: usize = #field_count;
+            /// The names of the fields returned by `destructure()`, in order.
+            const FIELD_NAMES: [&'static str; #field_count] = [#(#field_names,)*];
+            /// Whether the fields returned by `destructure()` need to be dropped, in order.
+            const NEEDS_DROP: [bool; #field_count] = [#(#needs_drop,)*];
+        }
+    }
+}
+
+/// Generates the `parts_layout()` function for `#[destructure(parts_layout)]`.
+fn destructure_parts_layout(input: &DeriveInput, returned: &[(&Field, Member)], opts: &DestructureOpts) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let field_types = returned.iter().map(|&(f, _)| &f.ty);
+
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The layout of the tuple returned by `destructure()`.
+            #inline
+            const fn parts_layout() -> #core::alloc::Layout {
+                #core::alloc::Layout::new::<(#(#field_types,)*)>()
+            }
+        }
+    }
+}
+
+/// Generates the `foo_fields!` macro for `#[destructure(fields_macro)]`.
+fn destructure_fields_macro(input: &DeriveInput, returned: &[(&Field, Member)], macro_name: Option<&Ident>) -> TokenStream {
+    let macro_name = match macro_name {
+        Some(macro_name) => macro_name.clone(),
+        None => Ident::new(&format!("{}_fields", to_snake_case(&input.ident.to_string())), Span::call_site()),
+    };
+    let field_list = returned.iter().map(|&(f, ref member)| {
+        let ty = replace_self(input, &f.ty);
+        quote! {
+            #member: #ty
+        }
+    });
+
+    quote! {
+        macro_rules! #macro_name {
+            ($callback:ident) => {
+                $callback! { #(#field_list),* }
+            };
+        }
+    }
+}
+
+/// Generates the `FooFields` type alias for `#[destructure(fields_type)]`.
+fn destructure_fields_type(input: &DeriveInput, returned: &[(&Field, Member)], type_name: Option<&Ident>) -> TokenStream {
+    let vis = &input.vis;
+    let type_name = match type_name {
+        Some(type_name) => type_name.clone(),
+        None => Ident::new(&(input.ident.to_string()+"Fields"), Span::call_site()),
+    };
+    let field_types = returned.iter().map(|&(f, _)| replace_self(input, &f.ty));
+
+    // Bounds on the generic parameters of type aliases aren't enforced, and the compiler warns
+    // about them, but they're still needed for field types like `I::Item`, so they're kept.
+    let generics = &returned_generics(input, returned, true);
+    let where_clause = &generics.where_clause;
+
+    quote! {
+        #[allow(type_alias_bounds)]
+        #vis type #type_name #generics #where_clause = (#(#field_types,)*);
+    }
+}
+
+/// Generates a struct `FooWithout<Field>` for every returned field, for `#[destructure(rest)]`,
+/// with the other returned fields of the original struct.
+fn destructure_rest(input: &DeriveInput, returned: &[(&Field, Member)]) -> syn::Result<TokenStream> {
+    if let Data::Struct(ref data) = input.data {
+        if let Fields::Unnamed(_) = data.fields {
+            return Err(ErrorContext::new(input).error(
+                "#[destructure(rest)] doesn't work on tuple structs, because the take_<field> methods are named after the fields.".to_owned(),
+                "remove rest from #[destructure(...)]",
+            ));
+        }
+    }
+    let vis = &input.vis;
+    let structs = returned.iter().enumerate().map(|(i, (_, taken))| {
+        let rest_name = rest_name(input, taken);
+        let (others, generics) = rest_fields(input, returned, i);
+        let where_clause = &generics.where_clause;
+        let fields = others.iter().map(|&(f, ref member)| {
+            let ty = returned_type(input, f);
+            quote_spanned! {f.span()=>
+                #vis #member: #ty
+            }
+        });
+        quote! {
+            #vis struct #rest_name #generics #where_clause {
+                #(#fields,)*
+            }
+        }
+    });
+
+    Ok(quote! {
+        #(#structs)*
+    })
+}
+
+/// The name of the struct that `take_<field>()` returns the other fields in: `FooWithoutField`.
+fn rest_name(input: &DeriveInput, taken: &Member) -> Ident {
+    let field = quote!(#taken).to_string();
+    let field = field.trim_start_matches("r#");
+    Ident::new(&format!("{}Without{}", input.ident, to_pascal_case(field)), Span::call_site())
+}
+
+/// The returned fields except the `taken`th one, and the generic parameters that they use.
+fn rest_fields<'a>(input: &DeriveInput, returned: &[(&'a Field, Member)], taken: usize) -> (Vec<(&'a Field, Member)>, Generics) {
+    let others: Vec<(&Field, Member)> = returned.iter().enumerate().filter(|&(i, _)| i != taken).map(|(_, &(f, ref member))| (f, member.clone())).collect();
+    let generics = returned_generics(input, &others, false);
+    (others, generics)
+}
+
+/// Generates the `FooParts` struct for `#[destructure(named)]`, with the returned fields of the
+/// original struct.
+fn destructure_named_parts(input: &DeriveInput, returned: &[(&Field, Member)]) -> syn::Result<TokenStream> {
+    if let Data::Struct(ref data) = input.data {
+        if let Fields::Unnamed(_) = data.fields {
+            return Err(ErrorContext::new(input).error(
+                "#[destructure(named)] doesn't work on tuple structs, because their fields have no names.".to_owned(),
+                "remove named from #[destructure(...)]",
+            ));
+        }
+    }
+    let vis = &input.vis;
+    let parts_name = Ident::new(&(input.ident.to_string()+"Parts"), Span::call_site());
+    let generics = &returned_generics(input, returned, false);
+    let where_clause = &generics.where_clause;
+    let fields = returned.iter().map(|&(f, ref member)| {
+        let ty = returned_type(input, f);
+        quote_spanned! {f.span()=>
+            #vis #member: #ty
+        }
+    });
+
+    Ok(quote! {
+        #vis struct #parts_name #generics #where_clause {
+            #(#fields,)*
+        }
+    })
+}
+
+/// Generates the `FooPartsTuple` wrapper for `#[destructure(parts_tuple)]`, whose `Debug` impl
+/// prints the tuple like the struct it came from.
+fn destructure_parts_tuple(input: &DeriveInput, returned: &[(&Field, Member)]) -> TokenStream {
+    let core = core_path(input);
+    let vis = &input.vis;
+    let name = &input.ident;
+    let wrapper_name = Ident::new(&(name.to_string()+"PartsTuple"), Span::call_site());
+    let generics = &returned_generics(input, returned, false);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let field_types: Vec<Type> = returned.iter().map(|&(f, _)| replace_self(input, &f.ty)).collect();
+
+    let mut debug_generics = generics.clone();
+    for ty in &field_types {
+        debug_generics.make_where_clause().predicates.push(parse_quote!(#ty: #core::fmt::Debug));
+    }
+    let (_, _, debug_where_clause) = debug_generics.split_for_impl();
+
+    let indices = (0..field_types.len()).map(Index::from);
+    let display_name = name.to_string();
+    let debug_body = match returned.first() {
+        Some(&(_, Member::Named(_))) => {
+            let field_names = returned.iter().map(|(_, member)| quote!(#member).to_string());
+            quote! {
+                f.debug_struct(#display_name)
+                    #(.field(#field_names, &(self.0).#indices))*
+                    .finish()
+            }
+        }
+        Some(&(_, Member::Unnamed(_))) => quote! {
+            f.debug_tuple(#display_name)
+                #(.field(&(self.0).#indices))*
+                .finish()
+        },
+        None => quote! {
+            f.write_str(#display_name)
+        },
+    };
+
+    quote! {
+        #vis struct #wrapper_name #generics (pub (#(#field_types,)*)) #where_clause;
+
+        impl #impl_generics #core::fmt::Debug for #wrapper_name #ty_generics #debug_where_clause {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                #debug_body
+            }
+        }
+    }
+}
+
+/// Generates the constant that records the `justification = "..."` of a derive. Without one, it
+/// generates a `compile_error!` instead, which only takes effect in crates that are compiled with
+/// `--cfg derive_destructure_require_justification`. The cfg is checked in the generated code
+/// rather than here, so that it applies to the crate that opts in, and not to every crate that
+/// uses derive_destructure in the same build.
+fn justification_const(input: &DeriveInput, justification: Option<&String>, derive: &str, const_name: &str) -> TokenStream {
+    let justification = match justification {
+        Some(justification) => justification,
+        None => {
+            let message = format!(
+                "#[derive({})] on `{}` needs a #[{}(justification = \"...\")] that explains why it's fine to bypass Drop, because this crate is compiled with --cfg derive_destructure_require_justification.",
+                derive, input.ident, derive
+            );
+            return quote_spanned! {input.ident.span()=>
+                #[allow(unknown_lints, unexpected_cfgs)]
+                const _: () = {
+                    #[cfg(derive_destructure_require_justification)]
+                    compile_error!(#message);
+                };
+            };
+        }
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let const_name = Ident::new(const_name, Span::call_site());
+    let doc = format!(" Why `#[derive({})]` may bypass `Drop` for this type.", derive);
+    quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            #[allow(dead_code)]
+            const #const_name: &'static str = #justification;
+        }
+    }
+}
+
+/// Converts a field name like `foo_bar` to `FooBar`.
+fn to_pascal_case(name: &str) -> String {
+    name.split('_').map(|word| {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    }).collect()
+}
+
+/// Converts a type name like `FooBar` to `foo_bar`.
+fn to_snake_case(name: &str) -> String {
+    let mut result = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 && !result.ends_with('_') {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Generates the `FooPatch` type for `#[destructure(patch)]`, along with
+/// `Foo::apply(self, patch: FooPatch) -> Foo`.
+fn destructure_patch(input: &DeriveInput, fields: &Fields, opts: &DestructureOpts) -> TokenStream {
+    let inline = opts.inline;
+    let core = core_path(input);
+    let name = &input.ident;
+    let vis = &input.vis;
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let patch_name = Ident::new(&(name.to_string()+"Patch"), Span::call_site());
+    let members = field_members(fields);
+
+    let patch_fields = fields.iter().map(|f| {
+        let vis = &f.vis;
+        let ty = replace_self(input, &f.ty);
+        match f.ident {
+            Some(ref ident) => quote_spanned! {f.span()=>
+                #vis #ident: #core::option::Option<#ty>
+            },
+            None => quote_spanned! {f.span()=>
+                #vis #core::option::Option<#ty>
+            },
+        }
+    });
+    let definition = match *fields {
+        Fields::Named(_) => quote! {
+            #vis struct #patch_name #generics #where_clause {
+                #(#patch_fields,)*
+            }
+        },
+        Fields::Unnamed(_) => quote! {
+            #vis struct #patch_name #generics (#(#patch_fields,)*) #where_clause;
+        },
+        Fields::Unit => quote! {
+            #vis struct #patch_name #generics #where_clause;
+        },
+    };
+
+    let field_updates = fields.iter().zip(&members).map(|(f, member)| {
+        let read = read_field(input, f, member);
+        quote_spanned! {f.span()=>
+            #member: match patch.#member {
+                #core::option::Option::Some(new_value) => {
+                    let _ = #read;
+                    new_value
+                }
+                #core::option::Option::None => #read,
+            }
+        }
+    });
+
+    let defaults = members.iter().map(|member| quote!(#member: #core::option::Option::None));
+    let body = consume_self(input, quote! {
+        #name {
+            #(#field_updates,)*
+        }
+    });
+    quote! {
+        #definition
+
+        impl #impl_generics #core::default::Default for #patch_name #ty_generics #where_clause {
+            #inline
+            fn default() -> Self {
+                #patch_name {
+                    #(#defaults,)*
+                }
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            #inline
+            fn apply(self, patch: #patch_name #ty_generics) -> Self {
+                #body
+            }
+        }
+    }
+}
+
+#[proc_macro_derive(remove_trait_impls, attributes(remove_trait_impls))]
+pub fn derive_remove_trait_impls(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    proc_macro::TokenStream::from(expand_remove_trait_impls(input).unwrap_or_else(syn::Error::into_compile_error))
+}
+
+/// The same as `#[derive(remove_trait_impls)]`.
+#[proc_macro_derive(RemoveTraitImpls, attributes(remove_trait_impls))]
+pub fn derive_remove_trait_impls_pascal_case(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    derive_remove_trait_impls(input)
+}
+
+fn expand_remove_trait_impls(input: DeriveInput) -> syn::Result<TokenStream> {
+    let opts = remove_trait_impls_opts(&input)?;
+    let original_vis = input.vis.clone();
+    let input = if opts.module.is_some() {
+        visibility_in_module(input)
+    } else {
+        input
+    };
+    let cx = ErrorContext::new(&input);
+    let method_vis = opts.method_vis();
+    let name = &input.ident;
+    let core = core_path(&input);
+    let inline = opts.inline;
+
+    let new_type_name = opts.type_name(cx, name)?;
+    let mut errors = Errors::default();
+
+    if opts.owned && (opts.assert_layout || opts.try_restore.is_some() || opts.reverse_from) {
+        errors.push(cx.error(
+            "#[remove_trait_impls(owned)] can't be combined with assert_layout, try_restore or reverse_from, because the generated type has different fields.".to_owned(),
+            "remove either owned, or assert_layout, try_restore and reverse_from",
+        ));
+    }
+    if !opts.keep.is_empty() {
+        if opts.repr_c_tagged {
+            errors.push(cx.error(
+                "#[remove_trait_impls(keep(...))] can't be combined with repr_c_tagged, because the generated type isn't an enum.".to_owned(),
+                "remove either keep(...), or repr_c_tagged",
+            ));
+        }
+        if let Data::Union(_) = input.data {
+            errors.push(cx.error(
+                "#[remove_trait_impls(keep(...))] doesn't work on unions, because the active field isn't known.".to_owned(),
+                "remove keep(...) from #[remove_trait_impls(...)]",
+            ));
+        }
+        if opts.debug.is_some() && opts.keep.iter().any(|trait_| trait_ == "Debug") {
+            errors.push(cx.error(
+                "#[remove_trait_impls(keep(Debug))] can't be combined with debug, because both implement Debug.".to_owned(),
+                "remove either Debug from keep(...), or debug",
+            ));
+        }
+    }
+    if opts.repr_c_tagged {
+        match input.data {
+            Data::Enum(_) => {}
+            _ => errors.push(cx.error(
+                "#[remove_trait_impls(repr_c_tagged)] only works on enums.".to_owned(),
+                "remove repr_c_tagged, and add #[repr(C)] to the struct itself if needed",
+            )),
+        }
+        if !input.generics.params.is_empty() {
+            errors.push(cx.error(
+                "#[remove_trait_impls(repr_c_tagged)] doesn't work on generic enums.".to_owned(),
+                "remove the generic parameters from the enum, or remove repr_c_tagged",
+            ));
+        }
+        if opts.debug.is_some() || opts.assert_layout || opts.try_restore.is_some() || opts.reverse_from {
+            errors.push(cx.error(
+                "#[remove_trait_impls(repr_c_tagged)] can't be combined with debug, assert_layout, try_restore or reverse_from, because the generated type isn't an enum.".to_owned(),
+                "remove either repr_c_tagged, or debug, assert_layout, try_restore and reverse_from",
+            ));
+        }
+    }
+    if let Data::Union(_) = input.data {
+        if !has_fixed_layout(&input) {
+            errors.push(cx.error(
+                "#[derive(remove_trait_impls)] only works on unions with a #[repr(C)] attribute, because the union is copied as a whole.".to_owned(),
+                "add #[repr(C)] to the union",
+            ));
+        }
+        if opts.owned || opts.debug.is_some() || opts.try_restore.is_some() || opts.reverse_from {
+            errors.push(cx.error(
+                "#[remove_trait_impls(owned)], debug, try_restore and reverse_from don't work on unions, because the active field isn't known.".to_owned(),
+                "remove owned, debug, try_restore and reverse_from",
+            ));
+        }
+    }
+    errors.check(check_cfg_fields(&input));
+    errors.check(check_no_field_options(&input));
+    if opts.debug.is_some() && is_packed(&input) {
+        errors.push(cx.error(
+            "#[remove_trait_impls(debug)] doesn't work on #[repr(packed)] structs, because their fields can't be borrowed.".to_owned(),
+            "remove debug from #[remove_trait_impls(...)], or derive Debug with derive(Debug) if the fields are Copy",
+        ));
+    }
+    if !opts.keep.is_empty() && is_packed(&input) {
+        errors.push(cx.error(
+            "#[remove_trait_impls(keep(...))] doesn't work on #[repr(packed)] structs, because their fields can't be borrowed.".to_owned(),
+            "remove keep(...) from #[remove_trait_impls(...)], or derive the traits with derive(...) if the fields are Copy",
+        ));
+    }
+    errors.finish()?;
+    let (generics, mirror_generics) = remove_trait_impls_generics(&input, &opts);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (mirror_impl_generics, mirror_ty_generics, mirror_where_clause) = mirror_generics.split_for_impl();
+
+    let (layout_assertion, layout_check) = if opts.assert_layout {
+        remove_trait_impls_layout_assertion(&input, &new_type_name)
+    } else {
+        (TokenStream::new(), TokenStream::new())
+    };
+
+    let justification = justification_const(&input, opts.justification.as_ref(), "remove_trait_impls", "REMOVE_TRAIT_IMPLS_JUSTIFICATION");
+    let method = Ident::new("remove_trait_impls", Span::call_site());
+    let doc = opts.justification.as_ref().map(|justification| {
+        let doc = format!(" Created without calling `drop()` because: {}", justification);
+        quote!(#[doc = #doc])
+    });
+    let mut derives = opts.derives.clone();
+    if let Some(ref except) = opts.keep_derives {
+        derives.extend(kept_derives(&input, except));
+    }
+    let derive = if derives.is_empty() {
+        TokenStream::new()
+    } else {
+        quote!(#[derive(#(#derives),*)])
+    };
+    let mut output = remove_trait_impls_type(&input, &opts, &new_type_name, &method, quote!(#doc #derive), &layout_check);
+    for (variant_name, variant_opts) in &opts.variants {
+        let variant_type_name = Ident::new(&format!("{}{}", name, variant_name), Span::call_site());
+        let variant_method = Ident::new(&format!("into_{}", to_snake_case(&variant_name.to_string())), Span::call_site());
+        let mut derives = variant_opts.derives.clone();
+        if let Some(ref except) = variant_opts.keep_derives {
+            derives.extend(kept_derives(&input, except));
+        }
+        let attrs = if derives.is_empty() {
+            TokenStream::new()
+        } else {
+            quote!(#[derive(#(#derives),*)])
+        };
+        output.extend(remove_trait_impls_type(&input, &opts, &variant_type_name, &variant_method, attrs, &TokenStream::new()));
+    }
+
+    let unsafe_impls = opts.unsafe_impls.iter().map(|trait_| {
+        quote! {
+            unsafe impl #mirror_impl_generics #trait_ for #new_type_name #mirror_ty_generics #mirror_where_clause {}
+        }
+    });
+    let debug = match opts.debug {
+        Some(ref redacted) => remove_trait_impls_debug(&input, &mirror_generics, &new_type_name, redacted),
+        None => TokenStream::new(),
+    };
+    let keep = opts.keep.iter().map(|trait_| remove_trait_impls_keep(&input, &mirror_generics, &new_type_name, trait_, inline));
+    let try_restore = match opts.try_restore {
+        Some((ref validate, ref error)) => {
+            let restore = restore_original(&input, &new_type_name, quote!(self));
+            quote! {
+                impl #impl_generics #new_type_name #ty_generics #where_clause {
+                    /// Turns this value back into the original type, which means that its
+                    /// `drop()` method will be called again, but only if the validator accepts it.
+                    #inline
+                    #method_vis fn try_restore(self) -> #core::result::Result<#name #ty_generics, (Self, #error)> {
+                        match #validate(&self) {
+                            #core::result::Result::Ok(()) => #core::result::Result::Ok(#restore),
+                            #core::result::Result::Err(err) => #core::result::Result::Err((self, err)),
                         }
                     }
                 }
-                Fields::Unnamed(ref fields) => {
-                    let field_types = fields.unnamed.iter().map(|f| {
-                        let ty = &f.ty;
-                        quote_spanned! {f.span()=>
-                            #ty
-                        }
-                    });
-                    let field_reads = fields.unnamed.iter().enumerate().map(|(i,f)| {
-                        let index = Index::from(i);
-                        quote_spanned! {f.span()=>
-                            ::std::ptr::read(&self_ref.#index)
-                        }
-                    });
-                    quote! {
-                        impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn destructure(self) -> (#(#field_types,)*) {
-                                let maybe_uninit = ::std::mem::MaybeUninit::new(self);
-                                unsafe {
-                                    let self_ref = &*maybe_uninit.as_ptr();
-                                    (#(#field_reads,)*)
-                                }
-                            }
-                        }
-                    }
+            }
+        }
+        None => TokenStream::new(),
+    };
+    let reverse_from = if opts.reverse_from {
+        let restore = restore_original(&input, &new_type_name, quote!(value));
+        quote! {
+            impl #impl_generics #core::convert::From<#new_type_name #ty_generics> for #name #ty_generics #where_clause {
+                /// Turns the value back into the original type, which means that its `drop()`
+                /// method will be called again.
+                #inline
+                fn from(value: #new_type_name #ty_generics) -> Self {
+                    #restore
                 }
-                Fields::Unit => {
-                    quote! {
-                        impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn destructure(self) {
-                                let _ = ::std::mem::MaybeUninit::new(self);
-                            }
-                        }
-                    }
+            }
+        }
+    } else {
+        TokenStream::new()
+    };
+    let impl_trait = if opts.impl_trait {
+        quote! {
+            impl #impl_generics ::derive_destructure_traits::RemoveTraitImpls for #name #ty_generics #where_clause {
+                type Output = #new_type_name #mirror_ty_generics;
+
+                #inline
+                fn remove_trait_impls(self) -> Self::Output {
+                    #name::#method(self)
                 }
             }
         }
-        Data::Enum(_) => panic!("#[derive(destructure)] doesn't work on enums, use #[derive(remove_trait_impls)] instead."),
-        Data::Union(_) => panic!("#[derive(destructure)] doesn't work on unions."),
+    } else {
+        TokenStream::new()
+    };
+
+    let output = quote! {
+        #output
+        #(#unsafe_impls)*
+        #debug
+        #(#keep)*
+        #layout_assertion
+        #try_restore
+        #reverse_from
+        #impl_trait
+    };
+    let output = match opts.module {
+        Some(ref module) => quote! {
+            #original_vis mod #module {
+                use super::*;
+                #output
+            }
+        },
+        None => output,
     };
 
-    proc_macro::TokenStream::from(output)
+    let output = quote! {
+        #output
+        #justification
+    };
+    Ok(finish_output(output))
 }
 
-#[proc_macro_derive(remove_trait_impls)]
-pub fn derive_remove_trait_impls(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
+/// Makes the type and its fields one module more visible, for
+/// `#[remove_trait_impls(module = "...")]`, so that the types generated in the module are as
+/// visible as they would be next to the original type.
+fn visibility_in_module(mut input: DeriveInput) -> DeriveInput {
+    input.vis = vis_in_module(&input.vis);
+    let fields: Vec<&mut Field> = match input.data {
+        Data::Struct(ref mut data) => data.fields.iter_mut().collect(),
+        Data::Union(ref mut data) => data.fields.named.iter_mut().collect(),
+        // The fields of enum variants are as visible as the enum.
+        Data::Enum(_) => Vec::new(),
+    };
+    for f in fields {
+        f.vis = vis_in_module(&f.vis);
+    }
+    input
+}
 
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+/// Returns `vis` as it has to be written in a child module to mean the same.
+fn vis_in_module(vis: &Visibility) -> Visibility {
+    match *vis {
+        Visibility::Inherited => parse_quote!(pub(super)),
+        Visibility::Restricted(ref restricted) => {
+            let path = &restricted.path;
+            let first = path.segments.first().map(|segment| segment.ident.to_string());
+            match first.as_deref() {
+                Some("self") => {
+                    let rest = path.segments.iter().skip(1);
+                    parse_quote!(pub(in super #(::#rest)*))
+                }
+                Some("super") => parse_quote!(pub(in super::#path)),
+                _ => vis.clone(),
+            }
+        }
+        _ => vis.clone(),
+    }
+}
 
-    let new_type_name = Ident::new(&(name.to_string()+"WithoutTraitImpls"), Span::call_site());
+/// Generates the type without trait impls called `new_type_name`, along with a method called
+/// `method` on the original type that turns it into that type. `attrs` are put on the generated
+/// type, and `layout_check` goes at the start of the method.
+fn remove_trait_impls_type(input: &DeriveInput, opts: &RemoveTraitImplsOpts, new_type_name: &Ident, method: &Ident, attrs: TokenStream, layout_check: &TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let method_vis = opts.method_vis();
+    let vis = &input.vis;
+    let name = &input.ident;
+    let must_use = must_use_attr(input);
+    let (generics, mirror_generics) = remove_trait_impls_generics(input, opts);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (_, mirror_ty_generics, mirror_where_clause) = mirror_generics.split_for_impl();
+    // The `#[repr(...)]` attributes are copied, so that the generated type has the same layout
+    // for FFI. Explicit discriminants on enums with fields also require a primitive
+    // representation.
+    let repr_attrs: Vec<_> = input.attrs.iter().filter(|attr| attr.path().is_ident("repr")).collect();
+    let repr_attrs = &repr_attrs;
+    let type_docs = doc_attrs(&input.attrs);
+    let type_docs = &type_docs;
+    let mirror_type = |ty: &Type| {
+        let ty = replace_self(input, ty);
+        if opts.owned { owned_type(input, &ty) } else { ty }
+    };
+    let mirror_read = |ty: &Type, read: TokenStream| if opts.owned { to_owned(input, ty, read) } else { read };
 
-    let output = match input.data {
+    match input.data {
         Data::Struct(ref data) => {
             match data.fields {
                 Fields::Named(ref fields) => {
                     let fields_iter = fields.named.iter().map(|f| {
+                        let cfgs = cfg_attrs(&f.attrs);
+                        let docs = forwarded_attrs(&f.attrs, &opts.forward_field_attrs);
+                        let field_vis = &f.vis;
                         let ident = &f.ident;
-                        let ty = &f.ty;
+                        let ty = mirror_type(&f.ty);
                         quote_spanned! {f.span()=>
-                            #ident: #ty
+                            #(#cfgs)* #(#docs)* #field_vis #ident: #ty
                         }
                     });
                     let field_reads_iter = fields.named.iter().map(|f| {
+                        let cfgs = cfg_attrs(&f.attrs);
                         let ident = &f.ident;
-                        quote_spanned! {f.span()=>
-                            #ident: ::std::ptr::read(&self_ref.#ident)
+                        let read = mirror_read(&f.ty, read_field(input, f, ident));
+                        quote!(#(#cfgs)* #ident: #read)
+                    });
+                    let body = consume_self(input, quote! {
+                        #new_type_name {
+                            #(#field_reads_iter,)*
                         }
                     });
                     quote! {
-                        #[must_use]
-                        struct #new_type_name #ty_generics #where_clause {
+                        #(#type_docs)*
+                        #attrs
+                        #(#repr_attrs)*
+                        #must_use
+                        #vis struct #new_type_name #mirror_generics #mirror_where_clause {
                             #(#fields_iter,)*
                         }
 
                         impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn remove_trait_impls(self) -> #new_type_name #ty_generics {
-                                let maybe_uninit = ::std::mem::MaybeUninit::new(self);
-                                unsafe {
-                                    let self_ref = &*maybe_uninit.as_ptr();
-                                    #new_type_name {
-                                        #(#field_reads_iter,)*
-                                    }
-                                }
+                            #inline
+                            #method_vis fn #method(self) -> #new_type_name #mirror_ty_generics {
+                                #layout_check
+                                #body
                             }
                         }
                     }
                 }
                 Fields::Unnamed(ref fields) => {
                     let fields_iter = fields.unnamed.iter().map(|f| {
-                        let ty = &f.ty;
+                        let docs = forwarded_attrs(&f.attrs, &opts.forward_field_attrs);
+                        let field_vis = &f.vis;
+                        let ty = mirror_type(&f.ty);
                         quote_spanned! {f.span()=>
-                            #ty
+                            #(#docs)* #field_vis #ty
                         }
                     });
                     let field_reads_iter = fields.unnamed.iter().enumerate().map(|(i,f)| {
                         let index = Index::from(i);
-                        quote_spanned! {f.span()=>
-                            ::std::ptr::read(&self_ref.#index)
-                        }
+                        mirror_read(&f.ty, read_field(input, f, index))
+                    });
+                    let body = consume_self(input, quote! {
+                        #new_type_name(#(#field_reads_iter,)*)
                     });
                     quote! {
-                        #[must_use]
-                        struct #new_type_name #ty_generics #where_clause (#(#fields_iter,)*);
+                        #(#type_docs)*
+                        #attrs
+                        #(#repr_attrs)*
+                        #must_use
+                        #vis struct #new_type_name #mirror_generics (#(#fields_iter,)*) #mirror_where_clause;
 
                         impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn remove_trait_impls(self) -> #new_type_name #ty_generics {
-                                let maybe_uninit = ::std::mem::MaybeUninit::new(self);
-                                unsafe {
-                                    let self_ref = &*maybe_uninit.as_ptr();
-                                    #new_type_name(#(#field_reads_iter,)*)
-                                }
+                            #inline
+                            #method_vis fn #method(self) -> #new_type_name #mirror_ty_generics {
+                                #layout_check
+                                #body
                             }
                         }
                     }
                 }
                 Fields::Unit => {
+                    let forget = forget_self(input);
                     quote! {
-                        #[must_use]
-                        struct #new_type_name #ty_generics #where_clause;
+                        #(#type_docs)*
+                        #attrs
+                        #(#repr_attrs)*
+                        #must_use
+                        #vis struct #new_type_name #mirror_generics #mirror_where_clause;
 
                         impl #impl_generics #name #ty_generics #where_clause {
-                            #[inline(always)]
-                            fn remove_trait_impls(self) -> #new_type_name #ty_generics {
-                                let _ = ::std::mem::MaybeUninit::new(self);
+                            #inline
+                            #method_vis fn #method(self) -> #new_type_name #mirror_ty_generics {
+                                #layout_check
+                                #forget
                                 #new_type_name
                             }
                         }
@@ -262,55 +3322,74 @@ pub fn derive_remove_trait_impls(input: proc_macro::TokenStream) -> proc_macro::
                 }
             }
         }
+        Data::Enum(ref data) if opts.repr_c_tagged => {
+            remove_trait_impls_repr_c_tagged(input, data, opts, new_type_name, method, attrs, layout_check)
+        }
         Data::Enum(ref data) => {
             let variants_iter = data.variants.iter().map(|variant| {
+                let variant_cfgs = cfg_attrs(&variant.attrs);
+                let variant_docs = forwarded_attrs(&variant.attrs, &opts.forward_field_attrs);
                 let variant_ident = &variant.ident;
+                let discriminant = variant.discriminant.as_ref().map(|(eq, expr)| {
+                    quote!(#eq #expr)
+                });
                 match variant.fields {
                     Fields::Named(ref fields) => {
                         let fields_iter = fields.named.iter().map(|f| {
+                            let cfgs = cfg_attrs(&f.attrs);
+                            let docs = forwarded_attrs(&f.attrs, &opts.forward_field_attrs);
                             let ident = &f.ident;
-                            let ty = &f.ty;
+                            let ty = mirror_type(&f.ty);
                             quote_spanned! {f.span()=>
-                                #ident: #ty
+                                #(#cfgs)* #(#docs)* #ident: #ty
                             }
                         });
                         quote! {
+                            #(#variant_cfgs)*
+                            #(#variant_docs)*
                             #variant_ident {
                                 #(#fields_iter,)*
-                            }
+                            } #discriminant
                         }
                     }
                     Fields::Unnamed(ref fields) => {
                         let fields_iter = fields.unnamed.iter().map(|f| {
-                            let ty = &f.ty;
+                            let docs = forwarded_attrs(&f.attrs, &opts.forward_field_attrs);
+                            let ty = mirror_type(&f.ty);
                             quote_spanned! {f.span()=>
-                                #ty
+                                #(#docs)* #ty
                             }
                         });
                         quote! {
-                            #variant_ident(#(#fields_iter,)*)
+                            #(#variant_cfgs)*
+                            #(#variant_docs)*
+                            #variant_ident(#(#fields_iter,)*) #discriminant
                         }
                     }
                     Fields::Unit => {
-                        quote!(#variant_ident)
+                        quote!(#(#variant_cfgs)* #(#variant_docs)* #variant_ident #discriminant)
                     }
                 }
             });
             let match_arms_iter = data.variants.iter().map(|variant| {
+                let variant_cfgs = cfg_attrs(&variant.attrs);
                 let variant_ident = &variant.ident;
-                match variant.fields {
+                let arm = match variant.fields {
                     Fields::Named(ref fields) => {
                         let fields_iter = fields.named.iter().map(|f| {
+                            let cfgs = cfg_attrs(&f.attrs);
                             let ident = &f.ident;
                             quote_spanned! {f.span()=>
-                                ref #ident
+                                #(#cfgs)* ref #ident
                             }
                         });
                         let field_reads_iter = fields.named.iter().map(|f| {
+                            let cfgs = cfg_attrs(&f.attrs);
                             let ident = &f.ident;
-                            quote_spanned! {f.span()=>
-                                #ident: ::std::ptr::read(#ident)
-                            }
+                            let read = mirror_read(&f.ty, quote_spanned! {f.span()=>
+                                #core::ptr::read(#ident)
+                            });
+                            quote!(#(#cfgs)* #ident: #read)
                         });
                         quote! {
                             #name::#variant_ident { #(#fields_iter,)* } => #new_type_name::#variant_ident { #(#field_reads_iter,)* }
@@ -325,9 +3404,9 @@ pub fn derive_remove_trait_impls(input: proc_macro::TokenStream) -> proc_macro::
                         });
                         let field_reads_iter = fields.unnamed.iter().enumerate().map(|(i,f)| {
                             let index = Ident::new(&format!("__{}", i), f.span());
-                            quote_spanned! {f.span()=>
-                                ::std::ptr::read(#index)
-                            }
+                            mirror_read(&f.ty, quote_spanned! {f.span()=>
+                                #core::ptr::read(#index)
+                            })
                         });
                         quote! {
                             #name::#variant_ident(#(#fields_iter,)*) => #new_type_name::#variant_ident(#(#field_reads_iter,)*)
@@ -338,28 +3417,818 @@ pub fn derive_remove_trait_impls(input: proc_macro::TokenStream) -> proc_macro::
                             #name::#variant_ident => #new_type_name::#variant_ident
                         }
                     }
+                };
+                quote!(#(#variant_cfgs)* #arm)
+            });
+            let body = consume_self(input, quote! {
+                match *self_ptr {
+                    #(#match_arms_iter,)*
                 }
             });
             quote! {
-                enum #new_type_name #ty_generics #where_clause {
+                #(#type_docs)*
+                #attrs
+                #(#repr_attrs)*
+                #must_use
+                #vis enum #new_type_name #mirror_generics #mirror_where_clause {
                     #(#variants_iter,)*
                 }
 
                 impl #impl_generics #name #ty_generics #where_clause {
-                    #[inline(always)]
-                    fn remove_trait_impls(self) -> #new_type_name #ty_generics {
-                        let maybe_uninit = ::std::mem::MaybeUninit::new(self);
-                        unsafe {
-                            match &*maybe_uninit.as_ptr() {
-                                #(#match_arms_iter,)*
-                            }
+                    #inline
+                    #method_vis fn #method(self) -> #new_type_name #mirror_ty_generics {
+                        #layout_check
+                        #body
+                    }
+                }
+            }
+        }
+        Data::Union(ref data) => {
+            let fields_iter = data.fields.named.iter().map(|f| {
+                let cfgs = cfg_attrs(&f.attrs);
+                let docs = forwarded_attrs(&f.attrs, &opts.forward_field_attrs);
+                let field_vis = &f.vis;
+                let ident = &f.ident;
+                let ty = replace_self(input, &f.ty);
+                quote_spanned! {f.span()=>
+                    #(#cfgs)* #(#docs)* #field_vis #ident: #ty
+                }
+            });
+            // The active field isn't known, so the whole union is copied at once. That's only
+            // sound because both unions have the same `#[repr(C)]` layout.
+            let body = consume_self(input, quote! {
+                #core::ptr::read(self_ptr as *const #new_type_name #mirror_ty_generics)
+            });
+            quote! {
+                #(#type_docs)*
+                #attrs
+                #(#repr_attrs)*
+                #must_use
+                #vis union #new_type_name #mirror_generics #mirror_where_clause {
+                    #(#fields_iter,)*
+                }
+
+                impl #impl_generics #name #ty_generics #where_clause {
+                    #inline
+                    #method_vis fn #method(self) -> #new_type_name #mirror_ty_generics {
+                        #layout_check
+                        #body
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Replaces `Self` in the type of a field with the original type, because the field is put in a
+/// generated type, where `Self` would mean that type instead.
+fn replace_self(input: &DeriveInput, ty: &Type) -> Type {
+    let name = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut replace = ReplaceSelf {
+        original: parse_quote!(#name #ty_generics),
+    };
+    let mut ty = ty.clone();
+    replace.visit_type_mut(&mut ty);
+    ty
+}
+
+/// Replaces `Self` with `original` in everything it visits.
+struct ReplaceSelf {
+    original: Type,
+}
+
+impl ReplaceSelf {
+    /// Turns `Self::Item` into `<Foo<T>>::Item`.
+    fn replace_in_path(&self, qself: &mut Option<QSelf>, path: &mut Path) {
+        let starts_with_self = qself.is_none() && path.leading_colon.is_none() && path.segments.len() > 1
+            && path.segments[0].ident == "Self";
+        if starts_with_self {
+            *qself = Some(QSelf {
+                lt_token: Default::default(),
+                ty: Box::new(self.original.clone()),
+                position: 0,
+                as_token: None,
+                gt_token: Default::default(),
+            });
+            path.leading_colon = Some(Default::default());
+            path.segments = path.segments.clone().into_pairs().skip(1).collect();
+        }
+    }
+}
+
+impl VisitMut for ReplaceSelf {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if let Type::Path(TypePath { qself: None, ref path, .. }) = *ty {
+            if path.is_ident("Self") {
+                *ty = self.original.clone();
+                return;
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_type_path_mut(&mut self, ty: &mut TypePath) {
+        self.replace_in_path(&mut ty.qself, &mut ty.path);
+        visit_mut::visit_type_path_mut(self, ty);
+    }
+
+    fn visit_expr_path_mut(&mut self, expr: &mut ExprPath) {
+        self.replace_in_path(&mut expr.qself, &mut expr.path);
+        visit_mut::visit_expr_path_mut(self, expr);
+    }
+
+    // The tokens of a macro aren't parsed, so `Self` is replaced in them one token at a time.
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        let original = self.original.to_token_stream();
+        mac.tokens = replace_self_tokens(mem::take(&mut mac.tokens), &original);
+    }
+}
+
+/// Replaces `Self` in `tokens` with `original`, also inside groups.
+fn replace_self_tokens(tokens: TokenStream, original: &TokenStream) -> TokenStream {
+    let mut result = TokenStream::new();
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Ident(ref ident) if ident == "Self" => {
+                // `Self::Item` has to become `<Foo<T>>::Item`.
+                match tokens.peek() {
+                    Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => result.extend(quote!(<#original>)),
+                    _ => result.extend(original.clone()),
+                }
+            }
+            TokenTree::Group(ref group) => {
+                let mut replaced = Group::new(group.delimiter(), replace_self_tokens(group.stream(), original));
+                replaced.set_span(group.span());
+                result.extend(Some(TokenTree::Group(replaced)));
+            }
+            token => result.extend(Some(token)),
+        }
+    }
+    result
+}
+
+/// Returns the `#[cfg(...)]` attributes in `attrs`, which have to be copied onto everything that
+/// is generated for a field or variant.
+fn cfg_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("cfg")).collect()
+}
+
+/// Returns the doc comments in `attrs`, which are copied onto the generated types and fields.
+fn doc_attrs(attrs: &[Attribute]) -> Vec<&Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("doc")).collect()
+}
+
+/// Returns the doc comments in `attrs` of a field or variant, and the attributes listed in
+/// `#[remove_trait_impls(forward_field_attrs(...))]`.
+fn forwarded_attrs<'a>(attrs: &'a [Attribute], forward: &[Ident]) -> Vec<&'a Attribute> {
+    attrs.iter().filter(|attr| {
+        attr.path().is_ident("doc") || forward.iter().any(|name| attr.path().is_ident(name))
+    }).collect()
+}
+
+/// Checks that no field of a tuple struct or tuple variant has a `#[cfg(...)]` attribute, because
+/// removing it would change the indices of the fields after it.
+fn check_cfg_fields(input: &DeriveInput) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    for f in all_fields(input) {
+        if f.ident.is_none() && !cfg_attrs(&f.attrs).is_empty() {
+            errors.push(cx.with_field(f).error(
+                "#[cfg(...)] on fields of tuple structs and tuple variants isn't supported, because it changes the indices of the other fields.".to_owned(),
+                "give the fields names, or put the #[cfg(...)] on the whole type or variant",
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// Checks that no field has a `#[remove_trait_impls(...)]` attribute, because there are no
+/// options for fields.
+fn check_no_field_options(input: &DeriveInput) -> syn::Result<()> {
+    let cx = ErrorContext::new(input);
+    let mut errors = Errors::default();
+    for f in all_fields(input) {
+        if f.attrs.iter().any(|attr| attr.path().is_ident("remove_trait_impls")) {
+            errors.push(cx.with_field(f).error(
+                "#[remove_trait_impls(...)] has no options for fields.".to_owned(),
+                "remove the attribute from the field, or put the options on the type",
+            ));
+        }
+    }
+    errors.finish()
+}
+
+/// Returns whether the type has a `#[repr(C)]` or `#[repr(transparent)]` attribute, which
+/// fixes its layout.
+fn has_fixed_layout(input: &DeriveInput) -> bool {
+    repr_items(input).iter().any(|item| item.path().is_ident("C") || item.path().is_ident("transparent"))
+}
+
+/// Generates the type without trait impls for `#[remove_trait_impls(repr_c_tagged)]`: a
+/// `#[repr(C)]` struct with a tag, and a `#[repr(C)]` union with a struct for every variant.
+fn remove_trait_impls_repr_c_tagged(input: &DeriveInput, data: &DataEnum, opts: &RemoveTraitImplsOpts, new_type_name: &Ident, method: &Ident, attrs: TokenStream, layout_check: &TokenStream) -> TokenStream {
+    let core = core_path(input);
+    let inline = opts.inline;
+    let method_vis = opts.method_vis();
+    let must_use = must_use_attr(input);
+    let vis = &input.vis;
+    let name = &input.ident;
+    let tag_name = Ident::new(&(new_type_name.to_string()+"Tag"), Span::call_site());
+    let payload_name = Ident::new(&(new_type_name.to_string()+"Payload"), Span::call_site());
+    let type_docs = doc_attrs(&input.attrs);
+    let mirror_type = |ty: &Type| {
+        let ty = replace_self(input, ty);
+        if opts.owned { owned_type(input, &ty) } else { ty }
+    };
+    let mirror_read = |ty: &Type, read: TokenStream| if opts.owned { to_owned(input, ty, read) } else { read };
+
+    // The tag keeps the discriminants, and the enum's `#[repr(...)]` if there is one.
+    let repr_attrs: Vec<_> = input.attrs.iter().filter(|attr| attr.path().is_ident("repr")).collect();
+    let tag_repr = if repr_attrs.is_empty() {
+        quote!(#[repr(C)])
+    } else {
+        quote!(#(#repr_attrs)*)
+    };
+    let tag_variants = data.variants.iter().map(|variant| {
+        let variant_cfgs = cfg_attrs(&variant.attrs);
+        let variant_ident = &variant.ident;
+        let discriminant = variant.discriminant.as_ref().map(|(eq, expr)| {
+            quote!(#eq #expr)
+        });
+        quote!(#(#variant_cfgs)* #variant_ident #discriminant)
+    });
+
+    let payload_struct_name = |variant_ident: &Ident| {
+        Ident::new(&format!("{}{}", new_type_name, variant_ident), Span::call_site())
+    };
+    let payload_structs = data.variants.iter().map(|variant| {
+        let variant_cfgs = cfg_attrs(&variant.attrs);
+        let struct_name = payload_struct_name(&variant.ident);
+        let payload_struct = match variant.fields {
+            Fields::Named(ref fields) => {
+                let fields_iter = fields.named.iter().map(|f| {
+                    let cfgs = cfg_attrs(&f.attrs);
+                    let ident = &f.ident;
+                    let ty = mirror_type(&f.ty);
+                    quote_spanned! {f.span()=>
+                        #(#cfgs)* #vis #ident: #ty
+                    }
+                });
+                quote! {
+                    #[repr(C)]
+                    #vis struct #struct_name {
+                        #(#fields_iter,)*
+                    }
+                }
+            }
+            Fields::Unnamed(ref fields) => {
+                let fields_iter = fields.unnamed.iter().map(|f| {
+                    let ty = mirror_type(&f.ty);
+                    quote_spanned! {f.span()=>
+                        #vis #ty
+                    }
+                });
+                quote! {
+                    #[repr(C)]
+                    #vis struct #struct_name(#(#fields_iter,)*);
+                }
+            }
+            Fields::Unit => {
+                quote! {
+                    #[repr(C)]
+                    #vis struct #struct_name;
+                }
+            }
+        };
+        quote!(#(#variant_cfgs)* #payload_struct)
+    });
+    let union_fields = data.variants.iter().map(|variant| {
+        let variant_cfgs = cfg_attrs(&variant.attrs);
+        let variant_ident = &variant.ident;
+        let struct_name = payload_struct_name(variant_ident);
+        quote!(#(#variant_cfgs)* #vis #variant_ident: #core::mem::ManuallyDrop<#struct_name>)
+    });
+
+    let match_arms = data.variants.iter().map(|variant| {
+        let variant_cfgs = cfg_attrs(&variant.attrs);
+        let variant_ident = &variant.ident;
+        let struct_name = payload_struct_name(variant_ident);
+        let members = field_members(&variant.fields);
+        let bindings: Vec<Ident> = members.iter().map(|member| {
+            match *member {
+                Member::Named(ref ident) => ident.clone(),
+                Member::Unnamed(ref index) => Ident::new(&format!("__{}", index.index), Span::call_site()),
+            }
+        }).collect();
+        let patterns = variant.fields.iter().zip(members.iter().zip(&bindings)).map(|(f, (member, binding))| {
+            let cfgs = cfg_attrs(&f.attrs);
+            match *member {
+                Member::Named(_) => quote!(#(#cfgs)* ref #binding),
+                Member::Unnamed(_) => quote!(#member: ref #binding),
+            }
+        });
+        let field_reads = variant.fields.iter().zip(members.iter().zip(&bindings)).map(|(f, (member, binding))| {
+            let cfgs = cfg_attrs(&f.attrs);
+            let read = mirror_read(&f.ty, quote!(#core::ptr::read(#binding)));
+            quote!(#(#cfgs)* #member: #read)
+        });
+        quote! {
+            #(#variant_cfgs)*
+            #name::#variant_ident { #(#patterns,)* } => #new_type_name {
+                tag: #tag_name::#variant_ident,
+                payload: #payload_name {
+                    #variant_ident: #core::mem::ManuallyDrop::new(#struct_name { #(#field_reads,)* }),
+                },
+            }
+        }
+    });
+    let body = consume_self(input, quote! {
+        match *self_ptr {
+            #(#match_arms,)*
+        }
+    });
+
+    quote! {
+        #tag_repr
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        #vis enum #tag_name {
+            #(#tag_variants,)*
+        }
+
+        #(#payload_structs)*
+
+        #[repr(C)]
+        #[allow(non_snake_case)]
+        #vis union #payload_name {
+            #(#union_fields,)*
+        }
+
+        #(#type_docs)*
+        #attrs
+        #must_use
+        #[repr(C)]
+        #vis struct #new_type_name {
+            #vis tag: #tag_name,
+            #vis payload: #payload_name,
+        }
+
+        impl #name {
+            #inline
+            #method_vis fn #method(self) -> #new_type_name {
+                #layout_check
+                #body
+            }
+        }
+    }
+}
+
+/// Returns the generics of the impl on the original type, and the generics of the generated type.
+fn remove_trait_impls_generics(input: &DeriveInput, opts: &RemoveTraitImplsOpts) -> (Generics, Generics) {
+    if opts.owned {
+        owned_generics(input)
+    } else {
+        (input.generics.clone(), input.generics.clone())
+    }
+}
+
+/// Returns the traits in the `#[derive(...)]` attributes of the original type, except for the
+/// derives of this crate and the ones in `except`. Only derives that come after
+/// `#[derive(remove_trait_impls)]` are visible to it.
+fn kept_derives(input: &DeriveInput, except: &[Ident]) -> Vec<Path> {
+    let mut derives = Vec::new();
+    for attr in &input.attrs {
+        if !attr.path().is_ident("derive") {
+            continue;
+        }
+        if let Ok(paths) = attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated) {
+            for ident in paths.into_iter().filter_map(|path| path.get_ident().cloned()) {
+                let is_own_derive = ["destructure", "Destructure", "remove_trait_impls", "RemoveTraitImpls"].iter().any(|derive| ident == derive);
+                if !is_own_derive && !except.contains(&ident) {
+                    derives.push(Path::from(ident));
+                }
+            }
+        }
+    }
+    derives
+}
+
+/// Generates the compile-time check for `#[remove_trait_impls(assert_layout)]` that the original
+/// type and the generated type have the same size and alignment. Returns the constant that
+/// performs the check, and a statement that forces the constant to be evaluated, which goes in
+/// `remove_trait_impls()`.
+fn remove_trait_impls_layout_assertion(input: &DeriveInput, new_type_name: &Ident) -> (TokenStream, TokenStream) {
+    let core = core_path(input);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let size_message = format!("`{}` and `{}` have different sizes", name, new_type_name);
+    let align_message = format!("`{}` and `{}` have different alignments", name, new_type_name);
+    let mut assertion = quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            const __REMOVE_TRAIT_IMPLS_SAME_LAYOUT: () = {
+                assert!(#core::mem::size_of::<Self>() == #core::mem::size_of::<#new_type_name #ty_generics>(), #size_message);
+                assert!(#core::mem::align_of::<Self>() == #core::mem::align_of::<#new_type_name #ty_generics>(), #align_message);
+            };
+        }
+    };
+    // Generic types can only be checked once they're instantiated, which happens when
+    // `remove_trait_impls()` is used. Other types can be checked right away.
+    if input.generics.params.is_empty() {
+        assertion.extend(quote! {
+            const _: () = #name::__REMOVE_TRAIT_IMPLS_SAME_LAYOUT;
+        });
+    }
+    let check = quote! {
+        let () = Self::__REMOVE_TRAIT_IMPLS_SAME_LAYOUT;
+    };
+    (assertion, check)
+}
+
+/// Implements `trait_` for the generated type for `#[remove_trait_impls(keep(...))]`, by
+/// delegating to the fields one by one, like `#[derive(...)]` would. The fields are only ever
+/// borrowed, so no copy of the value is made.
+fn remove_trait_impls_keep(input: &DeriveInput, generics: &Generics, new_type_name: &Ident, trait_: &Ident, inline: Inline) -> TokenStream {
+    let core = core_path(input);
+    if trait_ == "Debug" {
+        return remove_trait_impls_debug(input, generics, new_type_name, &[]);
+    }
+    let paths = variant_paths(input, new_type_name);
+    if trait_ == "PartialEq" {
+        let generics = with_field_bounds(input, generics, parse_quote!(#core::cmp::PartialEq));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let arms = paths.iter().map(|&(ref path, ref cfgs, fields)| {
+            let (self_pattern, self_bindings) = ref_pattern(path, fields, "__self_");
+            let (other_pattern, other_bindings) = ref_pattern(path, fields, "__other_");
+            let comparisons = fields.iter().zip(self_bindings.iter().zip(&other_bindings)).map(|(f, (self_binding, other_binding))| {
+                let cfgs = cfg_attrs(&f.attrs);
+                quote! {
+                    #(#cfgs)*
+                    {
+                        if #self_binding != #other_binding {
+                            return false;
+                        }
+                    }
+                }
+            });
+            quote! {
+                #(#cfgs)*
+                (&#self_pattern, &#other_pattern) => {
+                    #(#comparisons)*
+                    true
+                }
+            }
+        });
+        quote! {
+            impl #impl_generics #core::cmp::PartialEq for #new_type_name #ty_generics #where_clause {
+                #inline
+                fn eq(&self, other: &Self) -> bool {
+                    match (self, other) {
+                        #(#arms)*
+                        #[allow(unreachable_patterns)]
+                        _ => false,
+                    }
+                }
+            }
+        }
+    } else if trait_ == "Eq" {
+        let generics = with_field_bounds(input, generics, parse_quote!(#core::cmp::Eq));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        quote! {
+            impl #impl_generics #core::cmp::Eq for #new_type_name #ty_generics #where_clause {}
+        }
+    } else {
+        let generics = with_field_bounds(input, generics, parse_quote!(#core::hash::Hash));
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        // Like `#[derive(Hash)]`, variants with the same fields hash differently.
+        let discriminant = match input.data {
+            Data::Enum(_) => quote!(#core::hash::Hash::hash(&#core::mem::discriminant(self), state);),
+            _ => TokenStream::new(),
+        };
+        let arms = paths.iter().map(|&(ref path, ref cfgs, fields)| {
+            let (pattern, bindings) = ref_pattern(path, fields, "__self_");
+            let hashes = fields.iter().zip(&bindings).map(|(f, binding)| {
+                let cfgs = cfg_attrs(&f.attrs);
+                quote!(#(#cfgs)* #core::hash::Hash::hash(#binding, state);)
+            });
+            quote! {
+                #(#cfgs)*
+                #pattern => {
+                    #discriminant
+                    #(#hashes)*
+                }
+            }
+        });
+        quote! {
+            impl #impl_generics #core::hash::Hash for #new_type_name #ty_generics #where_clause {
+                #inline
+                fn hash<__H: #core::hash::Hasher>(&self, state: &mut __H) {
+                    match *self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the paths of the struct or of the variants of the generated type, along with the
+/// `#[cfg(...)]` attributes of the variants and their fields.
+fn variant_paths<'a>(input: &'a DeriveInput, new_type_name: &Ident) -> Vec<(TokenStream, Vec<&'a Attribute>, &'a Fields)> {
+    match input.data {
+        Data::Struct(ref data) => vec![(quote!(#new_type_name), Vec::new(), &data.fields)],
+        Data::Enum(ref data) => data.variants.iter().map(|variant| {
+            let variant_ident = &variant.ident;
+            (quote!(#new_type_name::#variant_ident), cfg_attrs(&variant.attrs), &variant.fields)
+        }).collect(),
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+/// Generates a pattern for `path` that binds every field by reference, to a variable named after
+/// the field with `prefix` in front. Returns the pattern and the variables.
+fn ref_pattern(path: &TokenStream, fields: &Fields, prefix: &str) -> (TokenStream, Vec<Ident>) {
+    let members = field_members(fields);
+    let bindings: Vec<Ident> = members.iter().map(|member| {
+        match *member {
+            Member::Named(ref ident) => Ident::new(&format!("{}{}", prefix, ident), Span::call_site()),
+            Member::Unnamed(ref index) => Ident::new(&format!("{}{}", prefix, index.index), Span::call_site()),
+        }
+    }).collect();
+    let patterns = fields.iter().zip(members.iter().zip(&bindings)).map(|(f, (member, binding))| {
+        let cfgs = cfg_attrs(&f.attrs);
+        quote!(#(#cfgs)* #member: ref #binding)
+    });
+    (quote!(#path { #(#patterns,)* }), bindings)
+}
+
+/// Generates an expression that moves the fields of `value`, a value of the generated type,
+/// into a value of the original type.
+fn restore_original(input: &DeriveInput, new_type_name: &Ident, value: TokenStream) -> TokenStream {
+    let name = &input.ident;
+    match input.data {
+        Data::Struct(ref data) => {
+            let field_moves = data.fields.iter().zip(field_members(&data.fields)).map(|(f, member)| {
+                let cfgs = cfg_attrs(&f.attrs);
+                quote!(#(#cfgs)* #member: #value.#member)
+            });
+            quote! {
+                #name {
+                    #(#field_moves,)*
+                }
+            }
+        }
+        Data::Enum(ref data) => {
+            let arms = data.variants.iter().map(|variant| {
+                let variant_cfgs = cfg_attrs(&variant.attrs);
+                let variant_ident = &variant.ident;
+                let fields = variant.fields.iter().zip(field_bindings(&variant.fields)).map(|(f, binding)| {
+                    let cfgs = cfg_attrs(&f.attrs);
+                    quote!(#(#cfgs)* #binding)
+                }).collect::<Vec<_>>();
+                let fields = &fields;
+                quote! {
+                    #(#variant_cfgs)*
+                    #new_type_name::#variant_ident { #(#fields,)* } => {
+                        #name::#variant_ident { #(#fields,)* }
+                    }
+                }
+            });
+            quote! {
+                match #value {
+                    #(#arms)*
+                }
+            }
+        }
+        Data::Union(_) => unreachable!(),
+    }
+}
+
+/// For `#[remove_trait_impls(owned)]`, returns the type that `T` is borrowed from if `ty` is
+/// `&T`, `&mut T` or `Cow<T>`, along with whether it's a `Cow`.
+fn borrowed_type(ty: &Type) -> Option<(&Type, bool)> {
+    match *ty {
+        Type::Reference(ref reference) => Some((&reference.elem, false)),
+        Type::Path(ref type_path) if type_path.qself.is_none() => {
+            let segment = type_path.path.segments.last()?;
+            if segment.ident != "Cow" {
+                return None;
+            }
+            match segment.arguments {
+                PathArguments::AngleBracketed(ref args) => {
+                    args.args.iter().filter_map(|arg| match *arg {
+                        GenericArgument::Type(ref ty) => Some((ty, true)),
+                        _ => None,
+                    }).next()
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the type of a field of the generated type for `#[remove_trait_impls(owned)]`.
+fn owned_type(input: &DeriveInput, ty: &Type) -> Type {
+    let alloc = alloc_path(input);
+    match borrowed_type(ty) {
+        Some((borrowed, _)) => parse_quote!(<#borrowed as #alloc::borrow::ToOwned>::Owned),
+        None => ty.clone(),
+    }
+}
+
+/// Converts `value`, a field of type `ty`, into a field of type `owned_type(input, ty)`.
+fn to_owned(input: &DeriveInput, ty: &Type, value: TokenStream) -> TokenStream {
+    let alloc = alloc_path(input);
+    match borrowed_type(ty) {
+        Some((_, true)) => quote!(#alloc::borrow::Cow::into_owned(#value)),
+        Some((_, false)) => quote!(#alloc::borrow::ToOwned::to_owned(&*#value)),
+        None => value,
+    }
+}
+
+/// For `#[remove_trait_impls(owned)]`, returns the generics of the `remove_trait_impls()` impl,
+/// and the generics of the generated type. Both require `T: ToOwned` for every borrowed `T`, and
+/// the generated type has no lifetime parameters, so that it can be `'static`.
+fn owned_generics(input: &DeriveInput) -> (Generics, Generics) {
+    let alloc = alloc_path(input);
+    let field_types: Vec<&Type> = match input.data {
+        Data::Struct(ref data) => data.fields.iter().map(|f| &f.ty).collect(),
+        Data::Enum(ref data) => data.variants.iter().flat_map(|v| v.fields.iter()).map(|f| &f.ty).collect(),
+        Data::Union(_) => unreachable!(),
+    };
+    let mut generics = input.generics.clone();
+    for ty in field_types {
+        if let Some((borrowed, _)) = borrowed_type(ty) {
+            generics.make_where_clause().predicates.push(parse_quote!(#borrowed: #alloc::borrow::ToOwned));
+        }
+    }
+
+    let mut mirror_generics = generics.clone();
+    mirror_generics.params = mirror_generics.params.into_iter().filter_map(|param| match param {
+        GenericParam::Lifetime(_) => None,
+        GenericParam::Type(mut param) => {
+            param.bounds = without_lifetime_bounds(&param.bounds);
+            Some(GenericParam::Type(param))
+        }
+        param => Some(param),
+    }).collect();
+    if let Some(ref mut where_clause) = mirror_generics.where_clause {
+        where_clause.predicates = where_clause.predicates.clone().into_iter().filter_map(|predicate| match predicate {
+            WherePredicate::Lifetime(_) => None,
+            WherePredicate::Type(mut predicate) => {
+                predicate.bounds = without_lifetime_bounds(&predicate.bounds);
+                if predicate.bounds.is_empty() {
+                    None
+                } else {
+                    Some(WherePredicate::Type(predicate))
+                }
+            }
+            predicate => Some(predicate),
+        }).collect();
+    }
+    (generics, mirror_generics)
+}
+
+/// Returns `bounds` without lifetime bounds like `T: 'a`.
+fn without_lifetime_bounds(bounds: &Punctuated<TypeParamBound, Token![+]>) -> Punctuated<TypeParamBound, Token![+]> {
+    bounds.iter().filter(|bound| !matches!(**bound, TypeParamBound::Lifetime(_))).cloned().collect()
+}
+
+/// Returns a copy of `generics` where every type parameter is bounded by `bound`.
+fn with_bound(generics: &Generics, bound: TypeParamBound) -> Generics {
+    let mut generics = generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+    generics
+}
+
+/// Returns a copy of `generics` where every type parameter is bounded by `bound`, and so is every
+/// associated type like `I::Item` that the fields of the original type refer to, like
+/// `#[derive(...)]` does.
+fn with_field_bounds(input: &DeriveInput, generics: &Generics, bound: TypeParamBound) -> Generics {
+    let mut generics = with_bound(generics, bound.clone());
+    let field_types: Vec<&Type> = match input.data {
+        Data::Struct(ref data) => data.fields.iter().map(|f| &f.ty).collect(),
+        Data::Enum(ref data) => data.variants.iter().flat_map(|variant| variant.fields.iter().map(|f| &f.ty)).collect(),
+        Data::Union(_) => unreachable!(),
+    };
+    let mut projections = Vec::new();
+    for ty in field_types {
+        associated_type_projections(&input.generics, ty.into_token_stream(), &mut projections);
+    }
+    for projection in projections {
+        generics.make_where_clause().predicates.push(parse_quote!(#projection: #bound));
+    }
+    generics
+}
+
+/// Adds the associated types of type parameters that `tokens` (of a type) refers to, like
+/// `I::Item`, to `projections`, unless they're already in it.
+fn associated_type_projections(generics: &Generics, tokens: TokenStream, projections: &mut Vec<TokenStream>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match *token {
+            TokenTree::Ident(ref ident) if generics.type_params().any(|param| param.ident == *ident) => {
+                // `I::Item`, but not `Foo::I::Item` or `<Self as Trait>::I`.
+                let after_path = i > 0 && match tokens[i - 1] {
+                    TokenTree::Punct(ref punct) => punct.as_char() == ':',
+                    _ => false,
+                };
+                if let (false, Some(TokenTree::Punct(first)), Some(TokenTree::Punct(second)), Some(TokenTree::Ident(assoc))) = (after_path, tokens.get(i + 1), tokens.get(i + 2), tokens.get(i + 3)) {
+                    if first.as_char() == ':' && second.as_char() == ':' {
+                        let projection = quote!(#ident::#assoc);
+                        if !projections.iter().any(|known| known.to_string() == projection.to_string()) {
+                            projections.push(projection);
                         }
                     }
                 }
             }
+            TokenTree::Group(ref group) => associated_type_projections(generics, group.stream(), projections),
+            _ => {}
+        }
+    }
+}
+
+/// Generates the `Debug` impl for `#[remove_trait_impls(debug)]`. Fields whose name or index is
+/// in `redacted` are printed as `***`.
+fn remove_trait_impls_debug(input: &DeriveInput, generics: &Generics, new_type_name: &Ident, redacted: &[String]) -> TokenStream {
+    let core = core_path(input);
+    let generics = with_field_bounds(input, generics, parse_quote!(#core::fmt::Debug));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // Generates a pattern that binds all fields by reference, along with the code that prints them.
+    let debug_fields = |path: TokenStream, display_name: String, fields: &Fields| {
+        let members = field_members(fields);
+        let bindings: Vec<Ident> = members.iter().map(|member| {
+            match *member {
+                Member::Named(ref ident) => ident.clone(),
+                Member::Unnamed(ref index) => Ident::new(&format!("__{}", index.index), Span::call_site()),
+            }
+        }).collect();
+        let values = members.iter().zip(&bindings).map(|(member, binding)| {
+            if redacted.contains(&quote!(#member).to_string()) {
+                quote!(&format_args!("***"))
+            } else {
+                quote!(#binding)
+            }
+        }).collect::<Vec<_>>();
+        match *fields {
+            Fields::Named(_) => {
+                // Fields can be removed by `#[cfg(...)]`, so they're added one statement at a time.
+                let patterns = fields.iter().zip(&bindings).map(|(field, binding)| {
+                    let cfgs = cfg_attrs(&field.attrs);
+                    quote!(#(#cfgs)* ref #binding)
+                });
+                let field_calls = fields.iter().zip(members.iter().zip(&values)).map(|(field, (member, value))| {
+                    let cfgs = cfg_attrs(&field.attrs);
+                    quote!(#(#cfgs)* builder.field(stringify!(#member), #value);)
+                });
+                quote! {
+                    #path { #(#patterns,)* } => {
+                        let mut builder = f.debug_struct(#display_name);
+                        #(#field_calls)*
+                        builder.finish()
+                    }
+                }
+            }
+            Fields::Unnamed(_) => quote! {
+                #path(#(ref #bindings,)*) => {
+                    f.debug_tuple(#display_name)
+                        #(.field(#values))*
+                        .finish()
+                }
+            },
+            Fields::Unit => quote! {
+                #path => f.write_str(#display_name)
+            },
+        }
+    };
+
+    let arms = match input.data {
+        Data::Struct(ref data) => {
+            vec![debug_fields(quote!(#new_type_name), new_type_name.to_string(), &data.fields)]
+        }
+        Data::Enum(ref data) => {
+            data.variants.iter().map(|variant| {
+                let variant_cfgs = cfg_attrs(&variant.attrs);
+                let variant_ident = &variant.ident;
+                let arm = debug_fields(quote!(#new_type_name::#variant_ident), variant_ident.to_string(), &variant.fields);
+                quote!(#(#variant_cfgs)* #arm)
+            }).collect()
         }
-        Data::Union(_) => panic!("#[derive(remove_trait_impls)] doesn't work on unions."),
+        Data::Union(_) => unreachable!(),
     };
 
-    proc_macro::TokenStream::from(output)
+    quote! {
+        impl #impl_generics #core::fmt::Debug for #new_type_name #ty_generics #where_clause {
+            fn fmt(&self, f: &mut #core::fmt::Formatter) -> #core::fmt::Result {
+                match *self {
+                    #(#arms,)*
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,169 @@
+//! Function-like macros that build on the code generated by the derives.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::parse::{Parse, ParseStream, Result};
+use syn::punctuated::Punctuated;
+use syn::{braced, Expr, ExprMatch, Member, Pat, Path, PathSegment, Token};
+
+/// The input of `destructure_let!(Foo { some_str, some_int } = expr)`.
+pub struct DestructureLet {
+    path: Path,
+    fields: TokenStream,
+    expr: TokenStream,
+}
+
+impl Parse for DestructureLet {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let path = input.parse()?;
+        let content;
+        braced!(content in input);
+        let fields = content.parse()?;
+        input.parse::<Token![=]>()?;
+        let expr: TokenStream = input.parse()?;
+        if expr.is_empty() {
+            return Err(input.error("expected an expression after `=`"));
+        }
+        Ok(DestructureLet { path, fields, expr })
+    }
+}
+
+/// Expands `destructure_let!(Foo { some_str, some_int } = expr)` to a `let` statement that
+/// matches the `FooParts` returned by `destructure_named()` with the same fields. The compiler
+/// then checks that no field is missing or misspelled.
+pub fn destructure_let(input: DestructureLet) -> TokenStream {
+    let DestructureLet { mut path, fields, expr } = input;
+    if let Some(last) = path.segments.last_mut() {
+        add_suffix(last, "Parts");
+    }
+    quote! {
+        let #path { #fields } = (#expr).destructure_named();
+    }
+}
+
+/// The input of `destructure_some!(expr => { some_str, some_int: renamed, .. })`.
+pub struct DestructureSome {
+    expr: Expr,
+    fields: Punctuated<SomeField, Token![,]>,
+}
+
+/// A field named in `destructure_some!`, like `some_str`, `mut some_str` or `some_int: renamed`.
+struct SomeField {
+    member: Member,
+    pat: TokenStream,
+}
+
+impl Parse for DestructureSome {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let expr = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let content;
+        braced!(content in input);
+        let mut fields = Punctuated::new();
+        while !content.peek(Token![..]) {
+            if content.is_empty() {
+                return Err(content.error("expected `..` after the fields; use `destructure_let!` to bind all of them"));
+            }
+            fields.push_value(content.parse()?);
+            fields.push_punct(content.parse()?);
+        }
+        content.parse::<Token![..]>()?;
+        if !content.is_empty() {
+            return Err(content.error("expected `..` to be last"));
+        }
+        Ok(DestructureSome { expr, fields })
+    }
+}
+
+impl Parse for SomeField {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(Token![mut]) {
+            let mutability: Token![mut] = input.parse()?;
+            let ident: Ident = input.parse()?;
+            return Ok(SomeField {
+                member: Member::Named(ident.clone()),
+                pat: quote!(#mutability #ident),
+            });
+        }
+        let member: Member = input.parse()?;
+        let pat = if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            let pat = Pat::parse_single(input)?;
+            quote!(#pat)
+        } else if let Member::Named(ref ident) = member {
+            quote!(#ident)
+        } else {
+            return Err(input.error("expected `:` and a pattern after a tuple field"));
+        };
+        Ok(SomeField { member, pat })
+    }
+}
+
+/// Expands `destructure_some!(expr => { some_str, .. })` to a `let` statement that binds the
+/// named fields of `expr.destructure_named()`. The other fields are dropped at the end of the
+/// block that moves the named ones out.
+pub fn destructure_some(input: DestructureSome) -> TokenStream {
+    let expr = &input.expr;
+    let members = input.fields.iter().map(|field| &field.member);
+    let pats = input.fields.iter().map(|field| &field.pat);
+    quote! {
+        let (#(#pats,)*) = {
+            let parts = (#expr).destructure_named();
+            (#(parts.#members,)*)
+        };
+    }
+}
+
+/// Expands `destructure_match!(expr { Foo::A(x) => ..., Foo::B => ... })` to a `match` on
+/// `expr.destructure()`, with the patterns rewritten to match the variants of `FooDestructured`.
+pub fn destructure_match(input: TokenStream) -> TokenStream {
+    let mut expr_match: ExprMatch = match syn::parse2(quote!(match #input)) {
+        Ok(expr_match) => expr_match,
+        Err(err) => return err.to_compile_error(),
+    };
+    for arm in &mut expr_match.arms {
+        destructured_pattern(&mut arm.pat);
+    }
+    let expr = &expr_match.expr;
+    let arms = &expr_match.arms;
+    quote! {
+        match (#expr).destructure() {
+            #(#arms)*
+        }
+    }
+}
+
+/// Rewrites a pattern like `Foo::A(x)` to `FooDestructured::A(x)`, also inside `|` patterns and
+/// guards. Patterns that don't name a variant, like `_`, are left alone.
+fn destructured_pattern(pat: &mut Pat) {
+    let path = match *pat {
+        Pat::Or(ref mut pat) => {
+            for case in pat.cases.iter_mut() {
+                destructured_pattern(case);
+            }
+            return;
+        }
+        Pat::Guard(ref mut pat) => return destructured_pattern(&mut pat.pat),
+        Pat::Paren(ref mut pat) => return destructured_pattern(&mut pat.pat),
+        Pat::Struct(ref mut pat) => &mut pat.path,
+        Pat::TupleStruct(ref mut pat) => &mut pat.path,
+        Pat::Path(ref mut pat) if pat.qself.is_none() => &mut pat.path,
+        Pat::Ident(ref mut pat) => {
+            if let Some((_, ref mut subpat)) = pat.subpat {
+                destructured_pattern(subpat);
+            }
+            return;
+        }
+        _ => return,
+    };
+    let len = path.segments.len();
+    if len >= 2 {
+        add_suffix(&mut path.segments[len - 2], "Destructured");
+    }
+}
+
+/// Adds `suffix` to the name of `segment`, so that `Foo` becomes `FooParts`.
+fn add_suffix(segment: &mut PathSegment, suffix: &str) {
+    segment.ident = Ident::new(&format!("{}{}", segment.ident, suffix), segment.ident.span());
+}
+
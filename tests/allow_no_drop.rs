@@ -0,0 +1,30 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(allow_no_drop)]
+struct Plain {
+	name: String,
+	count: u32
+}
+
+#[derive(destructure)]
+#[destructure(allow_no_drop)]
+enum Either<T> {
+	Left(T),
+	Right(String)
+}
+
+#[test]
+fn test_allow_no_drop() {
+	let (name, count) = Plain { name: "plain".to_owned(), count: 3 }.destructure();
+	assert_eq!((name.as_str(), count), ("plain", 3));
+	match Either::Left::<u32>(5).destructure() {
+		EitherDestructured::Left(value) => assert_eq!(value, 5),
+		EitherDestructured::Right(_) => panic!("expected Left"),
+	}
+	match Either::Right::<u32>("right".to_owned()).destructure() {
+		EitherDestructured::Right(value) => assert_eq!(value, "right"),
+		EitherDestructured::Left(_) => panic!("expected Right"),
+	}
+}
@@ -0,0 +1,49 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(as_parts)]
+struct Guard<'a, T> {
+	name: &'a str,
+	value: T,
+	#[destructure(skip)]
+	_log: Vec<String>
+}
+
+impl<'a, T> Drop for Guard<'a, T> {
+	fn drop(&mut self) {}
+}
+
+#[derive(destructure)]
+#[destructure(as_parts)]
+struct Pair(String, u32);
+
+impl Drop for Pair {
+	fn drop(&mut self) {}
+}
+
+#[derive(destructure)]
+#[destructure(as_parts, allow_no_drop)]
+struct Unit;
+
+#[test]
+fn test_as_parts() {
+	let guard = Guard { name: "guard", value: vec![1, 2], _log: Vec::new() };
+	let (name, value) = guard.as_parts();
+	assert_eq!(*name, "guard");
+	assert_eq!(*value, vec![1, 2]);
+	assert_eq!(guard.destructure(), ("guard", vec![1, 2]));
+}
+
+#[test]
+fn test_as_parts_tuple_struct() {
+	let pair = Pair("pair".to_owned(), 2);
+	assert_eq!(pair.as_parts(), (&"pair".to_owned(), &2));
+	assert_eq!(pair.destructure(), ("pair".to_owned(), 2));
+}
+
+#[test]
+fn test_as_parts_unit() {
+	Unit.as_parts();
+	Unit.destructure();
+}
@@ -0,0 +1,42 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(as_parts, as_parts_mut)]
+struct Counter<'a> {
+	name: String,
+	count: u32,
+	#[destructure(forget)]
+	drops: &'a Cell<u32>
+}
+
+impl<'a> Drop for Counter<'a> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(as_parts_mut, allow_no_drop)]
+struct Unit;
+
+#[test]
+fn test_as_parts_mut() {
+	let drops = Cell::new(0);
+	{
+		let mut counter = Counter { name: "counter".to_owned(), count: 1, drops: &drops };
+		let (name, count) = counter.as_parts_mut();
+		name.push('!');
+		*count += 1;
+		assert_eq!(counter.as_parts(), (&"counter!".to_owned(), &2));
+	}
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_as_parts_mut_unit() {
+	Unit.as_parts_mut();
+	Unit.destructure();
+}
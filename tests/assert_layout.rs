@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::mem::size_of;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(assert_layout)]
+struct Foo {
+	x: u8,
+	y: u64,
+	z: u16
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(assert_layout)]
+enum Bar<T> {
+	A(T),
+	B { x: u32 },
+	C
+}
+
+impl<T> Drop for Bar<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_assert_layout_struct() {
+	let foo = Foo { x: 1, y: 2, z: 3 }.remove_trait_impls();
+	assert_eq!(size_of::<Foo>(), size_of::<FooWithoutTraitImpls>());
+	assert_eq!((foo.x, foo.y, foo.z), (1, 2, 3));
+}
+
+#[test]
+fn test_assert_layout_generic_enum() {
+	let a = Bar::A(String::from("foo")).remove_trait_impls();
+	if let BarWithoutTraitImpls::A(s) = a {
+		assert_eq!(s, "foo");
+	} else {
+		panic!();
+	}
+	let b = Bar::<u8>::B { x: 5 }.remove_trait_impls();
+	if let BarWithoutTraitImpls::B { x } = b {
+		assert_eq!(x, 5);
+	} else {
+		panic!();
+	}
+	let _ = Bar::<()>::C.remove_trait_impls();
+}
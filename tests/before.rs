@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(before = "Self::flush", named, with_shell, boxed, alias = "into_fields")]
+struct Writer {
+	buffer: Vec<u8>,
+	written: Vec<u8>,
+	flushes: u32
+}
+
+impl Writer {
+	fn new() -> Self {
+		Writer { buffer: b"data".to_vec(), written: Vec::new(), flushes: 0 }
+	}
+
+	fn flush(&mut self) {
+		self.written.append(&mut self.buffer);
+		self.flushes += 1;
+	}
+}
+
+impl Drop for Writer {
+	fn drop(&mut self) {
+		panic!("Writer shouldn't be dropped");
+	}
+}
+
+fn flush_output(output: &mut Output) {
+	output.0.push('!');
+}
+
+#[derive(destructure)]
+#[destructure(before = "flush_output")]
+struct Output(String);
+
+impl Drop for Output {
+	fn drop(&mut self) {
+		panic!("Output shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_before() {
+	assert_eq!(Writer::new().destructure(), (Vec::new(), b"data".to_vec(), 1));
+	assert_eq!(Writer::new().into_fields(), (Vec::new(), b"data".to_vec(), 1));
+	let WriterParts { buffer, written, flushes } = Writer::new().destructure_named();
+	assert_eq!((buffer, written, flushes), (Vec::new(), b"data".to_vec(), 1));
+	let (parts, _shell) = Writer::new().destructure_with_shell();
+	assert_eq!(parts, (Vec::new(), b"data".to_vec(), 1));
+	assert_eq!(Box::new(Writer::new()).destructure_boxed(), (Vec::new(), b"data".to_vec(), 1));
+}
+
+#[test]
+fn test_before_free_function() {
+	assert_eq!(Output("output".to_owned()).destructure(), ("output!".to_owned(),));
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(boxed)]
+struct Large {
+	name: String,
+	buffer: [u8; 1 << 16],
+	count: u32,
+	#[destructure(skip)]
+	dropped: DropChecker,
+	#[destructure(forget)]
+	_forgotten: DropChecker
+}
+
+impl Drop for Large {
+	fn drop(&mut self) {
+		panic!("Large shouldn't be dropped");
+	}
+}
+
+struct DropChecker(Rc<Cell<bool>>);
+
+impl Drop for DropChecker {
+	fn drop(&mut self) {
+		self.0.set(true);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(boxed, rename = "into_parts")]
+struct Token;
+
+impl Drop for Token {
+	fn drop(&mut self) {
+		panic!("Token shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_boxed() {
+	let dropped = Rc::new(Cell::new(false));
+	let forgotten = Rc::new(Cell::new(false));
+	let large = Box::new(Large {
+		name: "large".to_owned(),
+		buffer: [3; 1 << 16],
+		count: 7,
+		dropped: DropChecker(Rc::clone(&dropped)),
+		_forgotten: DropChecker(Rc::clone(&forgotten)),
+	});
+	let (name, buffer, count) = large.destructure_boxed();
+	assert_eq!(name, "large");
+	assert_eq!(buffer[1000], 3);
+	assert_eq!(count, 7);
+	assert!(dropped.get());
+	assert!(!forgotten.get());
+}
+
+#[test]
+fn test_boxed_unit() {
+	Box::new(Token).into_parts_boxed();
+}
@@ -0,0 +1,68 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(debug, reverse_from)]
+struct Config {
+	name: String,
+	#[cfg(any())]
+	missing: NotDefined,
+	#[cfg(test)]
+	present: u32
+}
+
+impl Drop for Config {
+	fn drop(&mut self) {}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(debug, reverse_from)]
+enum Event {
+	Start { id: u32, #[cfg(any())] missing: NotDefined },
+	#[cfg(any())]
+	Missing(NotDefined),
+	Stop(u32)
+}
+
+impl Drop for Event {
+	fn drop(&mut self) {}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(repr_c_tagged)]
+enum Tagged {
+	A { x: u8, #[cfg(any())] missing: NotDefined },
+	#[cfg(any())]
+	Missing(NotDefined),
+	B(u16)
+}
+
+impl Drop for Tagged {
+	fn drop(&mut self) {}
+}
+
+#[test]
+fn test_cfg_struct() {
+	let config = Config { name: "config".to_owned(), present: 3 }.remove_trait_impls();
+	assert_eq!(format!("{:?}", config), "ConfigWithoutTraitImpls { name: \"config\", present: 3 }");
+	let config = Config::from(config);
+	assert_eq!((config.name.as_str(), config.present), ("config", 3));
+}
+
+#[test]
+fn test_cfg_enum() {
+	let start = Event::Start { id: 1 }.remove_trait_impls();
+	assert_eq!(format!("{:?}", start), "Start { id: 1 }");
+	assert!(matches!(Event::from(start), Event::Start { id: 1 }));
+	assert!(matches!(Event::Stop(2).remove_trait_impls(), EventWithoutTraitImpls::Stop(2)));
+}
+
+#[test]
+fn test_cfg_repr_c_tagged() {
+	let a = Tagged::A { x: 1 }.remove_trait_impls();
+	assert_eq!(a.tag, TaggedWithoutTraitImplsTag::A);
+	assert_eq!(unsafe { a.payload.A.x }, 1);
+	let b = Tagged::B(2).remove_trait_impls();
+	assert_eq!(b.tag, TaggedWithoutTraitImplsTag::B);
+	assert_eq!(unsafe { b.payload.B.0 }, 2);
+}
@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(cloned)]
+struct Snapshot<'a, T> {
+	name: String,
+	values: Vec<T>,
+	#[destructure(skip)]
+	drops: &'a Cell<u32>
+}
+
+impl<'a, T> Drop for Snapshot<'a, T> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(cloned, rename = "into_parts", allow_no_drop)]
+struct Unit;
+
+#[test]
+fn test_cloned() {
+	let drops = Cell::new(0);
+	{
+		let snapshot = Snapshot { name: "snapshot".to_owned(), values: vec![1, 2], drops: &drops };
+		assert_eq!(snapshot.destructure_cloned(), ("snapshot".to_owned(), vec![1, 2]));
+		assert_eq!(snapshot.name, "snapshot");
+	}
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_cloned_unit() {
+	Unit.into_parts_cloned();
+	Unit.into_parts();
+}
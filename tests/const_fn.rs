@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(const_fn, named, from_parts, alias = "into_fields")]
+struct Config {
+	name: &'static str,
+	retries: u32
+}
+
+impl Drop for Config {
+	fn drop(&mut self) {
+		panic!("Config shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(const_fn)]
+struct Holder<T> {
+	value: T,
+	#[destructure(forget)]
+	_extra: Vec<u8>
+}
+
+impl<T> Drop for Holder<T> {
+	fn drop(&mut self) {}
+}
+
+const PARTS: (&str, u32) = Config { name: "default", retries: 3 }.destructure();
+const NAMED: ConfigParts = Config { name: "other", retries: 5 }.destructure_named();
+const ROUNDTRIP: (&str, u32) = Config::from_parts(("roundtrip", 1)).into_fields();
+const VALUE: (u8,) = Holder { value: 7u8, _extra: Vec::new() }.destructure();
+
+#[test]
+fn test_const_fn() {
+	assert_eq!(PARTS, ("default", 3));
+	assert_eq!((NAMED.name, NAMED.retries), ("other", 5));
+	assert_eq!(ROUNDTRIP, ("roundtrip", 1));
+	assert_eq!(VALUE, (7,));
+}
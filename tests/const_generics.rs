@@ -0,0 +1,76 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, from_parts, fields_type, impl_trait, into_tuple, rest, with_fields)]
+struct Buf<const N: usize> {
+	data: [u8; N],
+	flushed: bool
+}
+
+impl<const N: usize> Drop for Buf<N> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure, remove_trait_impls)]
+enum Frame<T, const N: usize> {
+	Full([T; N]),
+	Empty
+}
+
+impl<T, const N: usize> Drop for Frame<T, N> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_const_generic_struct() {
+	let (data, flushed) = Buf { data: [1, 2, 3], flushed: true }.destructure();
+	assert_eq!(data, [1, 2, 3]);
+	assert!(flushed);
+	let parts = Buf::<2> { data: [4, 5], flushed: false }.destructure_named();
+	assert_eq!(parts.data, [4, 5]);
+	assert!(!parts.flushed);
+	let fields: BufFields<2> = Buf::from_parts(([6, 7], true)).destructure();
+	assert_eq!(fields, ([6, 7], true));
+	let buf = Buf { data: [8; 4], flushed: false }.remove_trait_impls();
+	assert_eq!(buf.data, [8; 4]);
+	assert!(!buf.flushed);
+}
+
+#[test]
+fn test_const_generic_enum() {
+	match Frame::Full([1u8, 2]).destructure() {
+		FrameDestructured::Full(data) => assert_eq!(data, [1, 2]),
+		FrameDestructured::Empty => panic!("expected Full"),
+	}
+	match Frame::<String, 3>::Empty.remove_trait_impls() {
+		FrameWithoutTraitImpls::Empty => {}
+		FrameWithoutTraitImpls::Full(_) => panic!("expected Empty"),
+	}
+}
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(fields_type)]
+struct Ring<T = u8, const N: usize = 4> where T: Copy {
+	items: [T; N],
+	head: usize
+}
+
+impl<T: Copy, const N: usize> Drop for Ring<T, N> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_const_generic_default() {
+	let fields: RingFields = Ring { items: [1, 2, 3, 4], head: 1 }.destructure();
+	assert_eq!(fields, ([1, 2, 3, 4], 1));
+	let ring: RingWithoutTraitImpls = Ring { items: [5; 4], head: 2 }.remove_trait_impls();
+	assert_eq!(ring.items, [5; 4]);
+	assert_eq!(ring.head, 2);
+}
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Point {
+	x: i32,
+	y: i32
+}
+
+#[derive(destructure)]
+struct Mixed<'a> {
+	id: u64,
+	name: String,
+	label: &'a str,
+	flags: (bool, [u8; 2]),
+	#[destructure(copy)]
+	origin: Point,
+	tags: Vec<u8>
+}
+
+impl<'a> Drop for Mixed<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+struct AllCopy(u8, char, #[destructure(copy)] Point);
+
+impl Drop for AllCopy {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_mixed_copy_fields() {
+	let mixed = Mixed {
+		id: 1,
+		name: "name".to_owned(),
+		label: "label",
+		flags: (true, [1, 2]),
+		origin: Point { x: 3, y: 4 },
+		tags: vec![5]
+	};
+	let (id, name, label, flags, origin, tags) = mixed.destructure();
+	assert_eq!(id, 1);
+	assert_eq!(name, "name");
+	assert_eq!(label, "label");
+	assert_eq!(flags, (true, [1, 2]));
+	assert_eq!(origin, Point { x: 3, y: 4 });
+	assert_eq!(tags, vec![5]);
+}
+
+#[test]
+fn test_all_copy_fields() {
+	let (a, b, point) = AllCopy(1, 'b', Point { x: 0, y: -1 }).destructure();
+	assert_eq!(a, 1);
+	assert_eq!(b, 'b');
+	assert_eq!(point.x + point.y, -1);
+}
@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate derive_destructure;
+
+mod facade {
+	pub use std::{default, mem, option, ptr};
+}
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(crate_path = "crate::facade", patch)]
+struct Guard {
+	name: String,
+	count: u32
+}
+
+impl Drop for Guard {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(crate_path = "crate::facade")]
+enum Token {
+	Word(String),
+	Number(u64)
+}
+
+impl Drop for Token {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_crate_path() {
+	let (name, count) = Guard { name: "guard".to_owned(), count: 1 }.destructure();
+	assert_eq!((name.as_str(), count), ("guard", 1));
+
+	let guard = Guard { name: "guard".to_owned(), count: 1 }.apply(GuardPatch { count: Some(2), ..Default::default() });
+	let guard = guard.remove_trait_impls();
+	assert_eq!((guard.name.as_str(), guard.count), ("guard", 2));
+
+	match Token::Word("word".to_owned()).remove_trait_impls() {
+		TokenWithoutTraitImpls::Word(word) => assert_eq!(word, "word"),
+		TokenWithoutTraitImpls::Number(_) => panic!("wrong variant"),
+	}
+	assert!(matches!(Token::Number(3).remove_trait_impls(), TokenWithoutTraitImpls::Number(3)));
+}
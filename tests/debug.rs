@@ -0,0 +1,64 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(debug(redact(password, token)))]
+struct Login<T> {
+	user: String,
+	password: String,
+	token: T,
+	attempts: u32
+}
+
+impl<T> Drop for Login<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(debug(redact(1, secret)))]
+enum Credentials {
+	Key(String, String),
+	Password {
+		user: String,
+		secret: String
+	},
+	Anonymous
+}
+
+impl Drop for Credentials {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_debug_struct_redacted() {
+	let login = Login {
+		user: "me".to_owned(),
+		password: "hunter2".to_owned(),
+		token: 1234,
+		attempts: 3
+	};
+	let login = login.remove_trait_impls();
+	assert_eq!(
+		format!("{:?}", login),
+		r#"LoginWithoutTraitImpls { user: "me", password: ***, token: ***, attempts: 3 }"#
+	);
+	assert_eq!(login.password, "hunter2");
+	assert_eq!(login.token, 1234);
+}
+
+#[test]
+fn test_debug_enum_redacted() {
+	let key = Credentials::Key("id".to_owned(), "hunter2".to_owned()).remove_trait_impls();
+	assert_eq!(format!("{:?}", key), r#"Key("id", ***)"#);
+	let password = Credentials::Password {
+		user: "me".to_owned(),
+		secret: "hunter2".to_owned()
+	}.remove_trait_impls();
+	assert_eq!(format!("{:?}", password), r#"Password { user: "me", secret: *** }"#);
+	let anonymous = Credentials::Anonymous.remove_trait_impls();
+	assert_eq!(format!("{:?}", anonymous), "Anonymous");
+}
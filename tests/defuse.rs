@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(defuse)]
+struct CancelGuard<T> {
+	payload: T,
+	#[destructure(skip)]
+	_cancel: Box<dyn Fn()>
+}
+
+impl<T> Drop for CancelGuard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(defuse)]
+struct Pair {
+	first: String,
+	second: i32
+}
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(defuse, guard)]
+struct Lock {
+	token: u64
+}
+
+impl Drop for Lock {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_defuse_payload() {
+	let guard = CancelGuard { payload: vec![1, 2, 3], _cancel: Box::new(|| ()) };
+	let payload: Vec<i32> = guard.defuse();
+	assert_eq!(payload, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_defuse_tuple() {
+	let (first, second) = Pair { first: "first".to_owned(), second: 2 }.defuse();
+	assert_eq!(first, "first");
+	assert_eq!(second, 2);
+}
+
+#[test]
+fn test_defuse_guard() {
+	let token: u64 = LockGuard::new(Lock { token: 7 }).into_parts();
+	assert_eq!(token, 7);
+}
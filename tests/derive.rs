@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(derive(Debug, Clone, PartialEq))]
+struct Point {
+	x: i32,
+	y: i32
+}
+
+impl Drop for Point {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(derive(Debug, Clone, Copy, PartialEq, Eq))]
+enum Direction {
+	Up,
+	Down(u8)
+}
+
+impl Drop for Direction {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_derive() {
+	let point = Point { x: 1, y: 2 }.remove_trait_impls();
+	let copy = point.clone();
+	assert_eq!(point, copy);
+	assert_eq!(format!("{:?}", copy), "PointWithoutTraitImpls { x: 1, y: 2 }");
+
+	let up = Direction::Up.remove_trait_impls();
+	let down = Direction::Down(3).remove_trait_impls();
+	assert_ne!(up, down);
+	assert_eq!(format!("{:?} {:?}", up, down), "Up Down(3)");
+}
@@ -0,0 +1,102 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+enum Guard<T> {
+	Locked { owner: String, value: T },
+	Shared(T, usize),
+	Released
+}
+
+impl<T> Drop for Guard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(into_parts, alias = "destructure")]
+enum Single {
+	Value(Vec<u8>)
+}
+
+impl Drop for Single {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_destructure_enum() {
+	let locked = Guard::Locked {
+		owner: "me".to_owned(),
+		value: 5
+	};
+	match locked.destructure() {
+		GuardDestructured::Locked(owner, value) => {
+			assert_eq!(owner, "me");
+			assert_eq!(value, 5);
+		}
+		_ => panic!("wrong variant"),
+	}
+	match Guard::Shared("shared".to_owned(), 2).destructure() {
+		GuardDestructured::Shared(value, count) => {
+			assert_eq!(value, "shared");
+			assert_eq!(count, 2);
+		}
+		_ => panic!("wrong variant"),
+	}
+	match Guard::<u8>::Released.destructure() {
+		GuardDestructured::Released => {}
+		_ => panic!("wrong variant"),
+	}
+}
+
+#[test]
+fn test_destructure_enum_naming() {
+	let SingleDestructured::Value(v) = Single::Value(vec![1]).into_parts();
+	assert_eq!(v, vec![1]);
+	let SingleDestructured::Value(v) = Single::Value(vec![2]).destructure();
+	assert_eq!(v, vec![2]);
+}
+
+#[derive(destructure)]
+#[destructure(try_into_variants)]
+enum Message {
+	Packet { header: u32, body: Vec<u8> },
+	Ping(u8),
+	Closed
+}
+
+impl Drop for Message {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_try_into_variants() {
+	let packet = Message::Packet {
+		header: 7,
+		body: vec![1, 2]
+	};
+	let packet = match packet.try_into_ping() {
+		Ok(_) => panic!("wrong variant"),
+		Err(packet) => packet,
+	};
+	let (header, body) = packet.try_into_packet().ok().unwrap();
+	assert_eq!(header, 7);
+	assert_eq!(body, vec![1, 2]);
+
+	let (n,) = Message::Ping(3).try_into_ping().ok().unwrap();
+	assert_eq!(n, 3);
+	assert!(Message::Closed.try_into_closed().is_ok());
+	match Message::Closed.try_into_packet() {
+		Ok(_) => panic!("wrong variant"),
+		Err(closed) => match closed.destructure() {
+			MessageDestructured::Closed => {}
+			MessageDestructured::Packet(header, body) => panic!("wrong variant: {} {:?}", header, body),
+			MessageDestructured::Ping(n) => panic!("wrong variant: {}", n),
+		},
+	}
+}
@@ -0,0 +1,33 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(named)]
+struct Rect<T> {
+	width: T,
+	height: T,
+	label: String
+}
+
+impl<T> Drop for Rect<T> {
+	fn drop(&mut self) {
+		panic!("Rect shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_destructure_let() {
+	let rect = Rect { width: 3, height: 4, label: "rect".to_owned() };
+	destructure_let!(Rect { label, width, height } = rect);
+	assert_eq!((width, height, label), (3, 4, "rect".to_owned()));
+}
+
+#[test]
+fn test_destructure_let_patterns() {
+	destructure_let!(self::Rect { width: w, height: _, label: ref name } = Rect {
+		width: 1.5,
+		height: 2.0,
+		label: "float".to_owned(),
+	});
+	assert_eq!((w, name.as_str()), (1.5, "float"));
+}
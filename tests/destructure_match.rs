@@ -0,0 +1,51 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(named)]
+enum Packet<T> {
+	Data { buf: Vec<u8>, meta: T },
+	Ack(u32),
+	Eof
+}
+
+impl<T> Drop for Packet<T> {
+	fn drop(&mut self) {
+		panic!("Packet shouldn't be dropped");
+	}
+}
+
+fn describe(packet: Packet<&'static str>) -> String {
+	destructure_match!(packet {
+		Packet::Data { buf, meta } if buf.is_empty() => format!("empty {}", meta),
+		Packet::Data { buf, .. } => {
+			format!("{} bytes", buf.len())
+		}
+		Packet::Ack(n @ 0..=9) | Packet::Ack(n @ 40..=49) => format!("ack {}", n),
+		Packet::Ack(_) => "other ack".to_owned(),
+		Packet::Eof => "eof".to_owned(),
+	})
+}
+
+#[test]
+fn test_destructure_match() {
+	assert_eq!(describe(Packet::Data { buf: Vec::new(), meta: "header" }), "empty header");
+	assert_eq!(describe(Packet::Data { buf: vec![1, 2, 3], meta: "body" }), "3 bytes");
+	assert_eq!(describe(Packet::Ack(3)), "ack 3");
+	assert_eq!(describe(Packet::Ack(42)), "ack 42");
+	assert_eq!(describe(Packet::Ack(100)), "other ack");
+	assert_eq!(describe(Packet::Eof), "eof");
+}
+
+#[test]
+fn test_destructure_match_moves_fields() {
+	let packet: Packet<String> = Packet::Data { buf: vec![7], meta: "owned".to_owned() };
+	let (buf, meta) = destructure_match!(packet {
+		Packet::Data { buf, meta } => (buf, meta),
+		other => panic!("unexpected {}", match other {
+			PacketDestructured::Ack(_) => "ack",
+			_ => "eof",
+		}),
+	});
+	assert_eq!((buf, meta.as_str()), (vec![7], "owned"));
+}
@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate derive_destructure;
+extern crate derive_destructure_traits;
+
+use derive_destructure_traits::{destructure_ok, destructure_some};
+
+#[derive(destructure)]
+#[destructure(impl_trait)]
+struct Guard<T> {
+	name: String,
+	value: T
+}
+
+impl<T> Drop for Guard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn open(name: &str) -> Result<Guard<u32>, String> {
+	if name.is_empty() {
+		Err("no name".to_owned())
+	} else {
+		Ok(Guard { name: name.to_owned(), value: 3 })
+	}
+}
+
+fn teardown(name: &str) -> Result<u32, String> {
+	let (_, value) = destructure_ok(open(name))?;
+	Ok(value)
+}
+
+#[test]
+fn test_destructure_ok() {
+	let (name, value) = destructure_ok(open("guard")).unwrap();
+	assert_eq!((name.as_str(), value), ("guard", 3));
+	assert_eq!(destructure_ok(open("")).err(), Some("no name".to_owned()));
+	assert_eq!(teardown("guard"), Ok(3));
+	assert_eq!(teardown(""), Err("no name".to_owned()));
+}
+
+#[test]
+fn test_destructure_some() {
+	let guard = Some(Guard { name: "guard".to_owned(), value: 'x' });
+	assert_eq!(destructure_some(guard), Some(("guard".to_owned(), 'x')));
+	assert_eq!(destructure_some(None::<Guard<char>>), None);
+}
@@ -0,0 +1,45 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+struct Flag<'a>(&'a Cell<bool>);
+
+impl<'a> Drop for Flag<'a> {
+	fn drop(&mut self) {
+		self.0.set(true);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(named)]
+struct Guard<'a> {
+	file: String,
+	_lock: Flag<'a>,
+	line: u32
+}
+
+impl<'a> Drop for Guard<'a> {
+	fn drop(&mut self) {
+		panic!("Guard shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_destructure_some() {
+	let dropped = Cell::new(false);
+	let guard = Guard { file: "main.rs".to_owned(), _lock: Flag(&dropped), line: 7 };
+	destructure_some!(guard => { file, .. });
+	assert!(dropped.get());
+	assert_eq!(file, "main.rs");
+}
+
+#[test]
+fn test_destructure_some_patterns() {
+	let dropped = Cell::new(false);
+	let guard = Guard { file: "lib.rs".to_owned(), _lock: Flag(&dropped), line: 1 };
+	destructure_some!(guard => { mut file, line: n, .. });
+	file.push('!');
+	assert!(dropped.get());
+	assert_eq!((file.as_str(), n), ("lib.rs!", 1));
+}
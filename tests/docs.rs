@@ -0,0 +1,43 @@
+//! Checks that the generated types are documented, because the original ones are.
+
+#![deny(missing_docs)]
+
+#[macro_use]
+extern crate derive_destructure;
+
+/// A connection.
+#[derive(remove_trait_impls)]
+pub struct Connection {
+	/// The address of the peer.
+	pub address: String,
+	/// The number of bytes sent.
+	pub sent: u64
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {}
+}
+
+/// A state.
+#[derive(remove_trait_impls)]
+pub enum State {
+	/// Not connected yet.
+	Idle,
+	/// Connected.
+	Connected {
+		/// Since when.
+		since: u64
+	}
+}
+
+impl Drop for State {
+	fn drop(&mut self) {}
+}
+
+#[test]
+fn test_docs() {
+	let connection = Connection { address: "localhost".to_owned(), sent: 5 }.remove_trait_impls();
+	assert_eq!((connection.address.as_str(), connection.sent), ("localhost", 5));
+	assert!(matches!(State::Idle.remove_trait_impls(), StateWithoutTraitImpls::Idle));
+	assert!(matches!(State::Connected { since: 1 }.remove_trait_impls(), StateWithoutTraitImpls::Connected { since: 1 }));
+}
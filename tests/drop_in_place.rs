@@ -0,0 +1,38 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+use std::mem::ManuallyDrop;
+
+#[derive(destructure)]
+#[destructure(drop_in_place)]
+struct Handle<'a> {
+	drops: &'a Cell<u32>,
+	id: u32
+}
+
+impl<'a> Drop for Handle<'a> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + self.id);
+	}
+}
+
+#[test]
+fn test_drop_original_in_place() {
+	let drops = Cell::new(0);
+	let mut handle = ManuallyDrop::new(Handle {
+		drops: &drops,
+		id: 5
+	});
+	unsafe {
+		Handle::drop_original_in_place(&mut *handle);
+	}
+	assert_eq!(drops.get(), 5);
+
+	let (drops_ref, id) = Handle {
+		drops: &drops,
+		id: 7
+	}.destructure();
+	assert_eq!(drops_ref.get(), 5);
+	assert_eq!(id, 7);
+}
@@ -91,3 +91,65 @@ fn test_generic_d() {
 		panic!();
 	}
 }
+
+#[derive(remove_trait_impls)]
+#[repr(u8)]
+pub enum WithDiscriminants {
+	A(u32) = 7,
+	B { x: u16 } = 9,
+	C = 42
+}
+
+impl Drop for WithDiscriminants {
+	fn drop(&mut self) {
+		panic!("We shouldn't drop this!");
+	}
+}
+
+fn discriminant_of(e: &WithDiscriminantsWithoutTraitImpls) -> u8 {
+	unsafe { *(e as *const WithDiscriminantsWithoutTraitImpls as *const u8) }
+}
+
+#[test]
+fn test_discriminants() {
+	let a = WithDiscriminants::A(1).remove_trait_impls();
+	assert_eq!(discriminant_of(&a), 7);
+	if let WithDiscriminantsWithoutTraitImpls::A(x) = a {
+		assert_eq!(x, 1);
+	} else {
+		panic!();
+	}
+	let b = WithDiscriminants::B { x: 2 }.remove_trait_impls();
+	assert_eq!(discriminant_of(&b), 9);
+	if let WithDiscriminantsWithoutTraitImpls::B { x } = b {
+		assert_eq!(x, 2);
+	} else {
+		panic!();
+	}
+	let c = WithDiscriminants::C.remove_trait_impls();
+	assert_eq!(discriminant_of(&c), 42);
+}
+
+const BASE: isize = 100;
+
+#[derive(remove_trait_impls)]
+pub enum Status {
+	Ok = 0,
+	Warning,
+	Error = 13,
+	Fatal = BASE + 1
+}
+
+impl Drop for Status {
+	fn drop(&mut self) {
+		panic!("We shouldn't drop this!");
+	}
+}
+
+#[test]
+fn test_fieldless_discriminants() {
+	assert_eq!(Status::Ok.remove_trait_impls() as isize, 0);
+	assert_eq!(Status::Warning.remove_trait_impls() as isize, 1);
+	assert_eq!(Status::Error.remove_trait_impls() as isize, 13);
+	assert_eq!(Status::Fatal.remove_trait_impls() as isize, 101);
+}
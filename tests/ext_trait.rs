@@ -0,0 +1,46 @@
+#[macro_use]
+extern crate derive_destructure;
+
+mod guards {
+	pub trait SomeTrait {}
+	impl SomeTrait for i32 {}
+
+	#[derive(destructure)]
+	#[destructure(ext_trait, alias = "into_parts")]
+	pub struct Guard<'a, T: SomeTrait> {
+		pub r: &'a T,
+		pub name: String
+	}
+
+	impl<'a, T: SomeTrait> Drop for Guard<'a, T> {
+		fn drop(&mut self) {
+			panic!("We don't want to drop this");
+		}
+	}
+}
+
+use guards::{Guard, GuardDestructureExt};
+
+#[test]
+fn test_ext_trait() {
+	let i = 5;
+	let guard = Guard {
+		r: &i,
+		name: "foo".to_owned()
+	};
+	let (r, name) = guard.destructure();
+	assert_eq!(*r, 5);
+	assert_eq!(name, "foo");
+}
+
+#[test]
+fn test_ext_trait_alias() {
+	let i = 6;
+	let guard = Guard {
+		r: &i,
+		name: "bar".to_owned()
+	};
+	let (r, name) = GuardDestructureExt::into_parts(guard);
+	assert_eq!(*r, 6);
+	assert_eq!(name, "bar");
+}
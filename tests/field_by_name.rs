@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+struct DropChecker(Rc<Cell<bool>>);
+
+impl Drop for DropChecker {
+	fn drop(&mut self) {
+		self.0.set(true);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(field_by_name)]
+struct Resources {
+	file: String,
+	socket: DropChecker
+}
+
+impl Drop for Resources {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(field_by_name)]
+struct Pair(u8, &'static str);
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_into_field_by_name() {
+	let dropped = Rc::new(Cell::new(false));
+	let resources = Resources {
+		file: "foo.txt".to_owned(),
+		socket: DropChecker(Rc::clone(&dropped))
+	};
+	let resources = resources.into_field_by_name("unknown").err().unwrap();
+	assert!(!dropped.get());
+	let file = resources.into_field_by_name("file").ok().unwrap();
+	assert_eq!(file.downcast_ref::<String>().unwrap(), "foo.txt");
+	assert!(dropped.get());
+}
+
+#[test]
+fn test_into_field_by_index() {
+	let s = Pair(1, "foo").into_field_by_name("1").ok().unwrap();
+	assert_eq!(*s.downcast::<&str>().unwrap(), "foo");
+	let (a, b) = Pair(1, "foo").destructure();
+	assert_eq!((a, b), (1, "foo"));
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(field_info)]
+struct Foo<T> {
+	x: u8,
+	#[destructure(skip)]
+	_skipped: String,
+	y: String,
+	z: T
+}
+
+impl<T> Drop for Foo<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(field_info)]
+struct Pair(u32, Vec<u32>);
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_field_info() {
+	assert_eq!(Foo::<u8>::FIELD_COUNT, 3);
+	assert_eq!(Foo::<u8>::FIELD_NAMES, ["x", "y", "z"]);
+	assert_eq!(Foo::<u8>::NEEDS_DROP, [false, true, false]);
+	assert_eq!(Foo::<Box<u8>>::NEEDS_DROP, [false, true, true]);
+}
+
+#[test]
+fn test_field_info_tuple_struct() {
+	assert_eq!(Pair::FIELD_COUNT, 2);
+	assert_eq!(Pair::FIELD_NAMES, ["0", "1"]);
+	assert_eq!(Pair::NEEDS_DROP, [false, true]);
+	let (a, b) = Pair(1, vec![2]).destructure();
+	assert_eq!((a, b), (1, vec![2]));
+}
+
+#[test]
+fn test_field_info_destructure() {
+	let foo = Foo { x: 1, _skipped: String::new(), y: "y".to_owned(), z: 'z' };
+	let (x, y, z) = foo.destructure();
+	assert_eq!((x, y.as_str(), z), (1, "y", 'z'));
+}
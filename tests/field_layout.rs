@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::mem::{align_of, size_of};
+
+#[derive(destructure)]
+#[destructure(field_layout)]
+struct Foo<T> {
+	x: u8,
+	y: u64,
+	z: T
+}
+
+impl<T> Drop for Foo<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_field_sizes() {
+	assert_eq!(Foo::<[u16; 3]>::FIELD_SIZES, &[1, 8, 6]);
+	assert_eq!(Foo::<()>::FIELD_SIZES, &[1, 8, 0]);
+}
+
+#[test]
+fn test_field_aligns() {
+	assert_eq!(Foo::<[u16; 3]>::FIELD_ALIGNS, &[1, align_of::<u64>(), 2]);
+	assert_eq!(Foo::<String>::FIELD_SIZES[2], size_of::<String>());
+}
+
+#[test]
+fn test_field_layout_destructure() {
+	let foo = Foo {
+		x: 1,
+		y: 2,
+		z: "foo"
+	};
+	let (x, y, z) = foo.destructure();
+	assert_eq!((x, y, z), (1, 2, "foo"));
+}
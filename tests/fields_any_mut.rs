@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(fields_any_mut)]
+struct Config {
+	name: String,
+	retries: u32,
+	verbose: bool
+}
+
+impl Drop for Config {
+	fn drop(&mut self) {
+	}
+}
+
+#[derive(destructure)]
+#[destructure(fields_any_mut)]
+struct Pair(i32, i32);
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+	}
+}
+
+#[test]
+fn test_fields_any_mut() {
+	let mut config = Config {
+		name: "config".to_owned(),
+		retries: 1,
+		verbose: false
+	};
+	for field in config.fields_any_mut().iter_mut() {
+		if let Some(retries) = field.downcast_mut::<u32>() {
+			*retries += 2;
+		} else if let Some(verbose) = field.downcast_mut::<bool>() {
+			*verbose = true;
+		}
+	}
+	let (name, retries, verbose) = config.destructure();
+	assert_eq!(name, "config");
+	assert_eq!(retries, 3);
+	assert!(verbose);
+}
+
+#[test]
+fn test_fields_any_mut_tuple_struct() {
+	let mut pair = Pair(1, 2);
+	{
+		let [first, second] = pair.fields_any_mut();
+		*first.downcast_mut::<i32>().unwrap() = 10;
+		*second.downcast_mut::<i32>().unwrap() = 20;
+	}
+	assert_eq!(pair.destructure(), (10, 20));
+}
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(fields_macro)]
+struct FooBar {
+	x: i64,
+	y: String
+}
+
+impl Drop for FooBar {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(fields_macro = "tuple_fields")]
+struct Tuple(u8, bool);
+
+impl Drop for Tuple {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+macro_rules! field_names {
+	($($name:tt: $ty:ty),*) => {
+		&[$(stringify!($name)),*]
+	};
+}
+
+macro_rules! field_types {
+	($($name:tt: $ty:ty),*) => {
+		&[$(stringify!($ty)),*]
+	};
+}
+
+#[test]
+fn test_fields_macro_named() {
+	let names: &[&str] = foo_bar_fields!(field_names);
+	assert_eq!(names, &["x", "y"]);
+	let types: &[&str] = foo_bar_fields!(field_types);
+	assert_eq!(types, &["i64", "String"]);
+	let (x, y) = FooBar { x: 1, y: "foo".to_owned() }.destructure();
+	assert_eq!(x, 1);
+	assert_eq!(y, "foo");
+}
+
+#[test]
+fn test_fields_macro_tuple() {
+	let names: &[&str] = tuple_fields!(field_names);
+	assert_eq!(names, &["0", "1"]);
+	let (a, b) = Tuple(1, true).destructure();
+	assert_eq!(a, 1);
+	assert!(b);
+}
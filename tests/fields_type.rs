@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate derive_destructure;
+
+pub trait SomeTrait {}
+impl SomeTrait for i32 {}
+
+#[derive(destructure)]
+#[destructure(fields_type)]
+pub struct Foo<'a, T: SomeTrait = i32> {
+	r: &'a T,
+	s: String
+}
+
+impl<'a, T: SomeTrait> Drop for Foo<'a, T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(fields_type = "BarParts")]
+struct Bar(u8, bool);
+
+impl Drop for Bar {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn take_foo_fields(fields: FooFields<'_>) -> i32 {
+	*fields.0
+}
+
+#[test]
+fn test_fields_type() {
+	let i = 5;
+	let foo = Foo {
+		r: &i,
+		s: "foo".to_owned()
+	};
+	let fields: FooFields<i32> = foo.destructure();
+	assert_eq!(fields.1, "foo");
+	assert_eq!(take_foo_fields(fields), 5);
+}
+
+#[test]
+fn test_fields_type_custom_name() {
+	let parts: BarParts = Bar(1, true).destructure();
+	assert_eq!(parts, (1, true));
+}
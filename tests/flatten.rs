@@ -0,0 +1,58 @@
+#[macro_use]
+extern crate derive_destructure;
+extern crate derive_destructure_traits;
+
+#[derive(destructure)]
+#[destructure(impl_trait)]
+struct Connection {
+	socket: String,
+	retries: u32
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {
+		panic!("Connection shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(named, impl_trait)]
+struct Session<T> {
+	id: u64,
+	#[destructure(flatten)]
+	connection: Connection,
+	user: T
+}
+
+impl<T> Drop for Session<T> {
+	fn drop(&mut self) {
+		panic!("Session shouldn't be dropped");
+	}
+}
+
+fn session() -> Session<&'static str> {
+	Session {
+		id: 7,
+		connection: Connection { socket: "localhost:80".to_owned(), retries: 2 },
+		user: "admin"
+	}
+}
+
+#[test]
+fn test_flatten() {
+	let (id, (socket, retries), user) = session().destructure();
+	assert_eq!((id, socket.as_str(), retries, user), (7, "localhost:80", 2, "admin"));
+}
+
+#[test]
+fn test_flatten_named() {
+	let SessionParts { id, connection: (socket, _), user } = session().destructure_named();
+	assert_eq!((id, socket.as_str(), user), (7, "localhost:80", "admin"));
+}
+
+#[test]
+fn test_flatten_trait() {
+	use derive_destructure_traits::Destructure;
+	let (_, (_, retries), _) = Destructure::destructure(session());
+	assert_eq!(retries, 2);
+}
@@ -0,0 +1,46 @@
+#![forbid(unsafe_code)]
+
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, with_shell, boxed, take_parts)]
+#[remove_trait_impls(reverse_from)]
+struct Connection {
+	id: u32,
+	name: String
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure, remove_trait_impls)]
+enum Message {
+	Text(String),
+	Quit
+}
+
+impl Drop for Message {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_forbid_unsafe_code() {
+	let (id, name) = Connection { id: 1, name: "db".to_owned() }.destructure();
+	assert_eq!((id, name.as_str()), (1, "db"));
+	let connection = Connection { id: 2, name: "db".to_owned() }.remove_trait_impls();
+	assert_eq!((connection.id, connection.name.as_str()), (2, "db"));
+	match Message::Text("hi".to_owned()).destructure() {
+		MessageDestructured::Text(text) => assert_eq!(text, "hi"),
+		MessageDestructured::Quit => panic!("expected Text"),
+	}
+	match Message::Quit.remove_trait_impls() {
+		MessageWithoutTraitImpls::Quit => {}
+		MessageWithoutTraitImpls::Text(_) => panic!("expected Quit"),
+	}
+}
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+struct Handle<'a> {
+	closes: &'a Cell<u32>
+}
+
+impl<'a> Drop for Handle<'a> {
+	fn drop(&mut self) {
+		self.closes.set(self.closes.get() + 1);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(with_shell)]
+struct Connection<'a> {
+	#[destructure(forget)]
+	handle: Handle<'a>,
+	#[destructure(skip)]
+	log: Handle<'a>,
+	#[destructure(forget)]
+	raw_fd: i32,
+	address: String
+}
+
+impl<'a> Drop for Connection<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn connection(closes: &Cell<u32>) -> Connection<'_> {
+	Connection {
+		handle: Handle { closes },
+		log: Handle { closes },
+		raw_fd: 3,
+		address: "localhost".to_owned()
+	}
+}
+
+#[test]
+fn test_forget() {
+	let closes = Cell::new(0);
+	let c = connection(&closes);
+	assert_eq!(c.raw_fd, 3);
+	assert_eq!(c.handle.closes.get(), 0);
+	let (address,) = c.destructure();
+	assert_eq!(address, "localhost");
+	// Only the skipped field is dropped.
+	assert_eq!(closes.get(), 1);
+}
+
+#[test]
+fn test_forget_with_shell() {
+	let closes = Cell::new(0);
+	let ((address,), _shell) = connection(&closes).destructure_with_shell();
+	assert_eq!(address, "localhost");
+	assert_eq!(closes.get(), 1);
+}
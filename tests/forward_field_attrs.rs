@@ -0,0 +1,50 @@
+#![deny(dead_code)]
+
+#[macro_use]
+extern crate derive_destructure;
+
+// Without forwarding `#[allow(dead_code)]`, the unused fields of the generated types would fail
+// to compile because of `#![deny(dead_code)]`.
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(forward_field_attrs(allow))]
+struct Request {
+	url: String,
+	#[allow(dead_code)]
+	retries: u32
+}
+
+impl Drop for Request {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(forward_field_attrs(allow))]
+enum Response {
+	Ok(String),
+	#[allow(dead_code)]
+	Redirect { location: String }
+}
+
+impl Drop for Response {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_forward_field_attrs() {
+	let request = Request { url: "/".to_owned(), retries: 3 }.remove_trait_impls();
+	assert_eq!(request.url, "/");
+
+	let response = Response::Redirect { location: "/".to_owned() };
+	if let Response::Redirect { ref location } = response {
+		assert_eq!(location, "/");
+	}
+	match Response::Ok("ok".to_owned()).remove_trait_impls() {
+		ResponseWithoutTraitImpls::Ok(body) => assert_eq!(body, "ok"),
+		_ => panic!("wrong variant"),
+	}
+	let _ = response.remove_trait_impls();
+}
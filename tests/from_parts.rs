@@ -0,0 +1,51 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(from_parts)]
+struct Guard {
+	name: String,
+	count: u32
+}
+
+impl Drop for Guard {
+	fn drop(&mut self) {}
+}
+
+#[derive(destructure)]
+#[destructure(from_parts, ext_trait)]
+struct Wrapper<T>(T, u8);
+
+impl<T> Drop for Wrapper<T> {
+	fn drop(&mut self) {}
+}
+
+#[derive(destructure)]
+#[destructure(from_parts)]
+struct Unit;
+
+impl Drop for Unit {
+	fn drop(&mut self) {}
+}
+
+#[test]
+fn test_from_parts() {
+	let guard = Guard { name: "guard".to_owned(), count: 1 };
+	let (name, count) = guard.destructure();
+	let guard = Guard::from_parts((name, count + 1));
+	assert_eq!(guard.name, "guard");
+	assert_eq!(guard.count, 2);
+}
+
+#[test]
+fn test_from_parts_tuple_struct() {
+	let wrapper = Wrapper::from_parts(("wrapper", 3));
+	assert_eq!(wrapper.0, "wrapper");
+	assert_eq!(wrapper.1, 3);
+	assert_eq!(wrapper.destructure(), ("wrapper", 3));
+}
+
+#[test]
+fn test_from_parts_unit() {
+	Unit::from_parts(()).destructure();
+}
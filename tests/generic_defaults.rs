@@ -0,0 +1,60 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+struct Buffer<T = u8> {
+	data: [T; 4],
+	len: usize
+}
+
+impl<T> Drop for Buffer<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+enum Either<L, R = L> {
+	Left(L),
+	Right(R)
+}
+
+impl<L, R> Drop for Either<L, R> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(ext_trait)]
+struct Pair<T = i32>(T, T);
+
+impl<T> Drop for Pair<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+// These only compile if the generated types and traits keep the defaults.
+fn default_buffer(buffer: Buffer) -> BufferWithoutTraitImpls {
+	buffer.remove_trait_impls()
+}
+
+fn default_either(either: Either<u32>) -> EitherWithoutTraitImpls<u32> {
+	either.remove_trait_impls()
+}
+
+fn destructure_pair<P: PairDestructureExt>(pair: P) -> (i32, i32) {
+	pair.destructure()
+}
+
+#[test]
+fn test_generic_defaults() {
+	let buffer = default_buffer(Buffer { data: [1, 2, 3, 4], len: 2 });
+	assert_eq!(&buffer.data[..buffer.len], &[1, 2]);
+
+	assert!(matches!(default_either(Either::Left(1)), EitherWithoutTraitImpls::Left(1)));
+	assert!(matches!(default_either(Either::Right(2)), EitherWithoutTraitImpls::Right(2)));
+
+	assert_eq!(destructure_pair(Pair(3, 4)), (3, 4));
+}
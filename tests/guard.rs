@@ -0,0 +1,69 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[derive(destructure)]
+#[destructure(guard)]
+struct Transaction {
+	id: u32,
+	rolled_back: Rc<Cell<bool>>
+}
+
+impl Drop for Transaction {
+	fn drop(&mut self) {
+		self.rolled_back.set(true);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(guard, named, threshold = 1)]
+struct Pair<T> {
+	first: T,
+	second: T
+}
+
+impl<T> Drop for Pair<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn transaction(id: u32) -> (TransactionGuard, Rc<Cell<bool>>) {
+	let rolled_back = Rc::new(Cell::new(false));
+	(TransactionGuard::new(Transaction { id, rolled_back: rolled_back.clone() }), rolled_back)
+}
+
+#[test]
+fn test_guard_into_parts() {
+	let (guard, rolled_back) = transaction(1);
+	assert_eq!(guard.id, 1);
+	let (id, _) = guard.into_parts();
+	assert_eq!(id, 1);
+	assert!(!rolled_back.get());
+}
+
+#[test]
+fn test_guard_dropped() {
+	let (mut guard, rolled_back) = transaction(2);
+	guard.id = 3;
+	drop(guard);
+	assert!(rolled_back.get());
+}
+
+#[test]
+fn test_guard_into_original() {
+	let (guard, rolled_back) = transaction(4);
+	let original = guard.into_original();
+	assert!(!rolled_back.get());
+	assert_eq!(original.id, 4);
+	drop(original);
+	assert!(rolled_back.get());
+}
+
+#[test]
+fn test_guard_parts_struct() {
+	let parts = PairGuard::new(Pair { first: 1, second: 2 }).into_parts();
+	assert_eq!((parts.first, parts.second), (1, 2));
+}
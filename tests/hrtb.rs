@@ -0,0 +1,66 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, fields_type, parts_tuple, rest, impl_trait, from_parts, with_fields)]
+#[remove_trait_impls(debug, impl_trait, reverse_from)]
+struct Filter<F, I> where for<'a> F: Fn(&'a str) -> &'a str, I: Iterator, I::Item: Clone {
+	filter: F,
+	input: I,
+	last: Option<I::Item>
+}
+
+impl<F, I> Drop for Filter<F, I> where for<'a> F: Fn(&'a str) -> &'a str, I: Iterator, I::Item: Clone {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure, remove_trait_impls)]
+enum Mapper<F: for<'a> Fn(&'a str) -> &'a str> {
+	Active(F),
+	Idle
+}
+
+impl<F: for<'a> Fn(&'a str) -> &'a str> Drop for Mapper<F> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn trim(s: &str) -> &str {
+	s.trim()
+}
+
+#[test]
+fn test_hrtb_struct() {
+	let (filter, mut input, last) = Filter { filter: trim, input: vec![1, 2].into_iter(), last: Some(0) }.destructure();
+	assert_eq!(filter(" a "), "a");
+	assert_eq!(input.next(), Some(1));
+	assert_eq!(last, Some(0));
+	let parts = Filter { filter: trim, input: 0..3, last: None }.destructure_named();
+	assert_eq!((parts.filter)(" b"), "b");
+	assert_eq!(parts.input, 0..3);
+	assert_eq!(parts.last, None);
+	let filter = Filter { filter: trim, input: 0..3, last: Some(5) }.remove_trait_impls();
+	assert_eq!((filter.filter)("c "), "c");
+	assert_eq!(filter.last, Some(5));
+	let filter = Filter { filter: trim as fn(&str) -> &str, input: 0..3, last: Some(5) }.remove_trait_impls();
+	assert!(format!("{:?}", filter).contains("last: Some(5)"));
+}
+
+#[test]
+fn test_hrtb_enum() {
+	match Mapper::Active(trim).destructure() {
+		MapperDestructured::Active(f) => assert_eq!(f(" d "), "d"),
+		MapperDestructured::Idle => panic!("expected Active"),
+	}
+	match Mapper::Active(trim).remove_trait_impls() {
+		MapperWithoutTraitImpls::Active(f) => assert_eq!(f(" e "), "e"),
+		MapperWithoutTraitImpls::Idle => panic!("expected Active"),
+	}
+	match Mapper::<fn(&str) -> &str>::Idle.destructure() {
+		MapperDestructured::Idle => {}
+		MapperDestructured::Active(_) => panic!("expected Idle"),
+	}
+}
@@ -0,0 +1,98 @@
+#[macro_use]
+extern crate derive_destructure;
+extern crate derive_destructure_traits;
+
+use derive_destructure_traits::{Destructure, RemoveTraitImpls};
+
+#[derive(destructure)]
+#[destructure(impl_trait)]
+struct Guard<T> {
+	name: String,
+	value: T
+}
+
+impl<T> Drop for Guard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(impl_trait, into_parts)]
+struct Empty;
+
+impl Drop for Empty {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(impl_trait)]
+enum Event {
+	Key(char),
+	Resize { width: u32, height: u32 }
+}
+
+impl Drop for Event {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn consume<T: Destructure>(t: T) -> T::Fields {
+	t.destructure()
+}
+
+#[test]
+fn test_impl_trait() {
+	let (name, value) = consume(Guard { name: "guard".to_owned(), value: 5u8 });
+	assert_eq!(name, "guard");
+	assert_eq!(value, 5);
+
+	consume(Empty);
+	Empty.into_parts();
+}
+
+#[test]
+fn test_impl_trait_enum() {
+	assert!(matches!(consume(Event::Key('a')), EventDestructured::Key('a')));
+	assert!(matches!(consume(Event::Resize { width: 1, height: 2 }), EventDestructured::Resize(1, 2)));
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(impl_trait)]
+struct Handle<'a> {
+	name: &'a str,
+	id: u32
+}
+
+impl<'a> Drop for Handle<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(impl_trait, owned)]
+struct Borrowed<'a>(&'a str);
+
+impl<'a> Drop for Borrowed<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn strip<T: RemoveTraitImpls>(t: T) -> T::Output {
+	t.remove_trait_impls()
+}
+
+#[test]
+fn test_impl_trait_remove_trait_impls() {
+	let handle = strip(Handle { name: "handle", id: 3 });
+	assert_eq!(handle.name, "handle");
+	assert_eq!(handle.id, 3);
+
+	let owned: BorrowedWithoutTraitImpls = strip(Borrowed("borrowed"));
+	assert_eq!(owned.0, "borrowed");
+}
@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(inline = "never", alias = "into_fields", patch)]
+#[remove_trait_impls(inline = "hint")]
+struct Buffer<T> {
+	data: Vec<T>,
+	len: usize
+}
+
+impl<T> Drop for Buffer<T> {
+	fn drop(&mut self) {
+		panic!("Buffer shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(inline = "hint")]
+enum Slot {
+	Full(String),
+	Empty
+}
+
+impl Drop for Slot {
+	fn drop(&mut self) {
+		panic!("Slot shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_inline() {
+	let buffer = Buffer { data: vec![1, 2], len: 2 };
+	assert_eq!(buffer.destructure(), (vec![1, 2], 2));
+	let buffer = Buffer { data: vec![3], len: 1 };
+	assert_eq!(buffer.into_fields(), (vec![3], 1));
+	let buffer = Buffer { data: vec![4], len: 1 }.apply(BufferPatch { len: Some(0), ..Default::default() });
+	let buffer = buffer.remove_trait_impls();
+	assert_eq!((buffer.data, buffer.len), (vec![4], 0));
+	match Slot::Full("full".to_owned()).destructure() {
+		SlotDestructured::Full(value) => assert_eq!(value, "full"),
+		SlotDestructured::Empty => panic!("expected Full"),
+	}
+	match Slot::Empty.destructure() {
+		SlotDestructured::Empty => {}
+		SlotDestructured::Full(_) => panic!("expected Empty"),
+	}
+}
@@ -0,0 +1,59 @@
+// The events go to `::log::debug!`, so this crate stands in for `log`.
+extern crate self as log;
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::RefCell;
+
+thread_local! {
+	static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+#[macro_export]
+macro_rules! debug {
+	($($arg:tt)*) => {
+		$crate::record(format!($($arg)*))
+	};
+}
+
+pub fn record(event: String) {
+	EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+fn events() -> Vec<String> {
+	EVENTS.with(|events| events.borrow_mut().split_off(0))
+}
+
+#[derive(destructure)]
+#[destructure(instrument = "log", named, alias = "into_parts")]
+struct Connection {
+	id: u32,
+	name: String
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_instrument() {
+	let (id, name) = Connection { id: 1, name: "db".to_owned() }.destructure();
+	assert_eq!((id, name.as_str()), (1, "db"));
+	let line = line!() - 2;
+	let events = events();
+	assert_eq!(events.len(), 1);
+	assert!(events[0].starts_with(&format!("bypassed Drop of `Connection` at {}:{}:", file!(), line)));
+}
+
+#[test]
+fn test_instrument_other_methods() {
+	let parts = Connection { id: 1, name: "db".to_owned() }.destructure_named();
+	assert_eq!((parts.id, parts.name.as_str()), (1, "db"));
+	let (id, _) = Connection { id: 2, name: "db".to_owned() }.into_parts();
+	assert_eq!(id, 2);
+	let events = events();
+	assert_eq!(events.len(), 2);
+	assert!(events.iter().all(|event| event.starts_with("bypassed Drop of `Connection` at ") && event.contains(file!())));
+}
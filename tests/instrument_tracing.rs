@@ -0,0 +1,48 @@
+// The events go to `::tracing::debug!`, so this crate stands in for `tracing`.
+extern crate self as tracing;
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::RefCell;
+
+thread_local! {
+	static EVENTS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+#[macro_export]
+macro_rules! debug {
+	(type_name = $type_name:expr, location = %$location:expr, $message:literal) => {
+		$crate::record(format!("{} of `{}` at {}", $message, $type_name, $location))
+	};
+}
+
+pub fn record(event: String) {
+	EVENTS.with(|events| events.borrow_mut().push(event));
+}
+
+fn events() -> Vec<String> {
+	EVENTS.with(|events| events.borrow_mut().split_off(0))
+}
+
+#[derive(destructure)]
+#[destructure(instrument = "tracing")]
+struct Connection {
+	id: u32,
+	name: String
+}
+
+impl Drop for Connection {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_instrument_tracing() {
+	let (id, name) = Connection { id: 1, name: "db".to_owned() }.destructure();
+	assert_eq!((id, name.as_str()), (1, "db"));
+	let line = line!() - 2;
+	let events = events();
+	assert_eq!(events.len(), 1);
+	assert!(events[0].starts_with(&format!("bypassed Drop of `Connection` at {}:{}:", file!(), line)));
+}
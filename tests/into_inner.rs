@@ -0,0 +1,40 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(into_inner)]
+struct Guard<T>(T);
+
+impl<T> Drop for Guard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(into_inner)]
+struct Named {
+	value: String,
+	#[destructure(skip)]
+	_log: Vec<String>
+}
+
+impl Drop for Named {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_into_inner() {
+	let guard = Guard(vec![1, 2, 3]);
+	assert_eq!(guard.into_inner(), vec![1, 2, 3]);
+	let (tuple,) = Guard(5).destructure();
+	assert_eq!(tuple, 5);
+}
+
+#[test]
+fn test_into_inner_skipped() {
+	let named = Named { value: "value".to_owned(), _log: vec!["log".to_owned()] };
+	assert_eq!(named.into_inner(), "value");
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_destructure;
+
+mod data {
+	#[derive(Debug, PartialEq)]
+	pub struct ConfigData<T> {
+		pub name: String,
+		pub value: T
+	}
+}
+
+#[derive(destructure)]
+#[destructure(into_struct = "data::ConfigData<T>", into_tuple)]
+struct Config<T> {
+	value: T,
+	#[destructure(skip)]
+	_log: Vec<String>,
+	name: String
+}
+
+impl<T> Drop for Config<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn config() -> Config<u32> {
+	Config { value: 5, _log: Vec::new(), name: "config".to_owned() }
+}
+
+#[test]
+fn test_into_struct() {
+	let data = config().destructure();
+	assert_eq!(data, data::ConfigData { name: "config".to_owned(), value: 5 });
+}
+
+#[test]
+fn test_into_struct_into_tuple() {
+	let (value, name): (u32, String) = config().into();
+	assert_eq!((value, name.as_str()), (5, "config"));
+}
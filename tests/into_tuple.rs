@@ -0,0 +1,47 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(into_tuple)]
+struct Guard<T> {
+	name: String,
+	value: T
+}
+
+impl<T> Drop for Guard<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(into_tuple, threshold = 1)]
+struct Pair {
+	a: u8,
+	b: Vec<u8>
+}
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn take_apart<T: Into<(String, u32)>>(value: T) -> (String, u32) {
+	value.into()
+}
+
+#[test]
+fn test_into_tuple() {
+	let (name, value): (String, i32) = Guard { name: "guard".to_owned(), value: 3 }.into();
+	assert_eq!((name.as_str(), value), ("guard", 3));
+	assert_eq!(take_apart(Guard { name: "generic".to_owned(), value: 4 }), ("generic".to_owned(), 4));
+}
+
+#[test]
+fn test_into_tuple_threshold() {
+	let (a, b) = <(u8, Vec<u8>)>::from(Pair { a: 1, b: vec![2] });
+	assert_eq!((a, b), (1, vec![2]));
+	let PairParts { a, b } = Pair { a: 3, b: vec![4] }.destructure();
+	assert_eq!((a, b), (3, vec![4]));
+}
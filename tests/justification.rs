@@ -0,0 +1,37 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(justification = "the file is flushed by the caller")]
+#[remove_trait_impls(justification = "the fields are dropped separately")]
+struct File<T> {
+	name: String,
+	contents: T
+}
+
+impl<T> Drop for File<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_justification() {
+	assert_eq!(File::<()>::DESTRUCTURE_JUSTIFICATION, "the file is flushed by the caller");
+	assert_eq!(File::<()>::REMOVE_TRAIT_IMPLS_JUSTIFICATION, "the fields are dropped separately");
+
+	let file = File {
+		name: "a".to_owned(),
+		contents: 1
+	};
+	let (name, contents) = file.destructure();
+	assert_eq!(name, "a");
+	assert_eq!(contents, 1);
+
+	let file = File {
+		name: "b".to_owned(),
+		contents: 2
+	}.remove_trait_impls();
+	assert_eq!(file.name, "b");
+	assert_eq!(file.contents, 2);
+}
@@ -0,0 +1,74 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(keep(Debug, PartialEq, Eq, Hash))]
+struct Secret<T> {
+	name: String,
+	value: T
+}
+
+impl<T> fmt::Debug for Secret<T> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "Secret({})", self.name)
+	}
+}
+
+impl<T> Drop for Secret<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(keep(Debug, PartialEq, Hash))]
+enum Number {
+	Small(u8),
+	Big { value: u64 },
+	Other(u64)
+}
+
+impl Drop for Number {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+	let mut hasher = DefaultHasher::new();
+	value.hash(&mut hasher);
+	hasher.finish()
+}
+
+#[test]
+fn test_keep() {
+	let secret = Secret { name: "key".to_owned(), value: vec![1, 2] }.remove_trait_impls();
+	// The fields are printed one by one, not with the impl of `Secret`.
+	assert_eq!(format!("{:?}", secret), "SecretWithoutTraitImpls { name: \"key\", value: [1, 2] }");
+	let same = Secret { name: "key".to_owned(), value: vec![1, 2] }.remove_trait_impls();
+	let other = Secret { name: "key".to_owned(), value: vec![3] }.remove_trait_impls();
+	assert!(secret == same);
+	assert!(secret != other);
+	assert_eq!(hash(&secret), hash(&same));
+	assert_ne!(hash(&secret), hash(&other));
+	// The fields are still there, and dropped only once.
+	assert_eq!((secret.name.as_str(), secret.value), ("key", vec![1, 2]));
+}
+
+#[test]
+fn test_keep_enum() {
+	let big = Number::Big { value: 4096 }.remove_trait_impls();
+	assert_eq!(format!("{:?}", big), "Big { value: 4096 }");
+	assert_eq!(format!("{:?}", Number::Small(255).remove_trait_impls()), "Small(255)");
+	assert!(big == Number::Big { value: 4096 }.remove_trait_impls());
+	assert!(big != Number::Big { value: 1 }.remove_trait_impls());
+	assert!(Number::Small(1).remove_trait_impls() != Number::Big { value: 1 }.remove_trait_impls());
+	// Variants with the same fields are still told apart.
+	assert!(big != Number::Other(4096).remove_trait_impls());
+	assert_ne!(hash(&big), hash(&Number::Other(4096).remove_trait_impls()));
+	assert_eq!(hash(&big), hash(&Number::Big { value: 4096 }.remove_trait_impls()));
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(keep_derives(except(Default)), variants(NoDefault(keep_derives(except(Clone, Default)))))]
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Foo {
+	a: u32,
+	b: String
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn foo() -> Foo {
+	Foo {
+		a: 1,
+		b: "b".to_owned()
+	}
+}
+
+// `FooWithoutTraitImpls` would conflict with this if it derived `Default`.
+impl Default for FooWithoutTraitImpls {
+	fn default() -> Self {
+		FooWithoutTraitImpls { a: 2, b: String::new() }
+	}
+}
+
+#[test]
+fn test_keep_derives() {
+	let without = foo().remove_trait_impls();
+	let copy = without.clone();
+	assert_eq!(without, copy);
+	assert_eq!(format!("{:?}", copy), "FooWithoutTraitImpls { a: 1, b: \"b\" }");
+	assert_eq!(FooWithoutTraitImpls::default().a, 2);
+	let FooWithoutTraitImpls { a, b } = without;
+	assert_eq!((a, b.as_str()), (1, "b"));
+}
+
+// `FooNoDefault` would conflict with this if it derived `Clone`.
+impl Clone for FooNoDefault {
+	fn clone(&self) -> Self {
+		FooNoDefault { a: self.a + 1, b: self.b.clone() }
+	}
+}
+
+#[test]
+fn test_keep_derives_variant() {
+	let no_default = foo().into_no_default();
+	assert_eq!(no_default.clone().a, 2);
+	assert_eq!(format!("{:?}", no_default), "FooNoDefault { a: 1, b: \"b\" }");
+	assert_eq!(no_default.b, "b");
+}
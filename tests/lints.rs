@@ -0,0 +1,50 @@
+//! The code generated by the derives doesn't trigger lints, even in crates that deny them.
+#![deny(missing_docs, dead_code, unused_qualifications, unsafe_op_in_unsafe_fn, clippy::all, clippy::pedantic, clippy::needless_lifetimes)]
+
+#[macro_use]
+extern crate derive_destructure;
+
+/// A struct with a lifetime, that's only ever taken apart.
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, rest, fields_type, parts_tuple, patch, with_fields, with_methods, replace_methods, from_parts, as_parts, as_parts_mut, cloned, take_parts, field_info, field_layout, parts_layout, boxed, with_shell, into_tuple)]
+#[remove_trait_impls(debug, reverse_from, assert_layout)]
+pub struct Borrowed<'a> {
+	name: &'a str,
+	count: u32
+}
+
+impl Drop for Borrowed<'_> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+/// An enum that's only ever taken apart.
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, try_into_variants)]
+#[remove_trait_impls(debug)]
+pub enum Event {
+	/// A key was pressed.
+	Key {
+		/// The key.
+		code: u32
+	},
+	/// Nothing happened.
+	Idle
+}
+
+impl Drop for Event {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_lints() {
+	let (name, count) = Borrowed { name: "lints", count: 1 }.destructure();
+	assert_eq!((name, count), ("lints", 1));
+	match (Event::Key { code: 5 }).destructure() {
+		EventDestructured::Key { code } => assert_eq!(code, 5),
+		EventDestructured::Idle => panic!("expected Key"),
+	}
+}
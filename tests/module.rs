@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate derive_destructure;
+
+mod shapes {
+	use std::fmt;
+
+	pub struct Label(pub String);
+
+	impl fmt::Debug for Label {
+		fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+			write!(f, "Label({})", self.0)
+		}
+	}
+
+	#[derive(remove_trait_impls)]
+	#[remove_trait_impls(module = "rect_parts", debug, variants(Bare), reverse_from)]
+	pub struct Rect {
+		pub width: u32,
+		pub(crate) height: u32,
+		label: Label
+	}
+
+	impl Drop for Rect {
+		fn drop(&mut self) {
+			panic!("We don't want to drop this");
+		}
+	}
+
+	#[derive(remove_trait_impls)]
+	#[remove_trait_impls(module = "circle_parts", try_restore(validate = "validate", error = "()"))]
+	struct Circle(u32);
+
+	impl Drop for Circle {
+		fn drop(&mut self) {}
+	}
+
+	fn validate(circle: &circle_parts::CircleWithoutTraitImpls) -> Result<(), ()> {
+		if circle.0 > 0 { Ok(()) } else { Err(()) }
+	}
+
+	pub fn rect() -> Rect {
+		Rect { width: 3, height: 4, label: Label("rect".to_owned()) }
+	}
+
+	pub fn label(rect: rect_parts::RectWithoutTraitImpls) -> String {
+		rect.label.0
+	}
+
+	pub fn into_bare(rect: Rect) -> rect_parts::RectBare {
+		rect.into_bare()
+	}
+
+	pub fn restore_circle(radius: u32) -> bool {
+		let circle = Circle(radius).remove_trait_impls();
+		circle.try_restore().is_ok()
+	}
+
+	pub fn remove(rect: Rect) -> rect_parts::RectWithoutTraitImpls {
+		rect.remove_trait_impls()
+	}
+}
+
+#[test]
+fn test_module() {
+	let rect = shapes::remove(shapes::rect());
+	assert_eq!(format!("{:?}", rect), "RectWithoutTraitImpls { width: 3, height: 4, label: Label(rect) }");
+	assert_eq!((rect.width, rect.height), (3, 4));
+	assert_eq!(shapes::label(rect), "rect");
+}
+
+#[test]
+fn test_module_methods() {
+	let shapes::rect_parts::RectBare { width, height, .. } = shapes::into_bare(shapes::rect());
+	assert_eq!((width, height), (3, 4));
+	assert!(shapes::restore_circle(1));
+	assert!(!shapes::restore_circle(0));
+}
+
+#[test]
+fn test_module_reverse_from() {
+	let rect = shapes::remove(shapes::rect());
+	let rect = shapes::Rect::from(rect);
+	assert_eq!(rect.width, 3);
+	std::mem::forget(rect);
+}
@@ -0,0 +1,73 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(msrv = "1.71")]
+#[remove_trait_impls(msrv = "1.71")]
+struct DropChecker(Rc<Cell<bool>>);
+
+impl Drop for DropChecker {
+	fn drop(&mut self) {
+		self.0.set(true);
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(msrv = "1.77.0")]
+enum Either {
+	Left(DropChecker),
+	Right {
+		checker: DropChecker
+	}
+}
+
+impl Drop for Either {
+	fn drop(&mut self) {
+		panic!("We shouldn't drop this!");
+	}
+}
+
+#[test]
+fn test_msrv_destructure() {
+	let dropped = Rc::new(Cell::new(false));
+	let (rc,) = DropChecker(Rc::clone(&dropped)).destructure();
+	assert!(!dropped.get());
+	assert_eq!(Rc::strong_count(&rc), 2);
+}
+
+#[test]
+fn test_msrv_remove_trait_impls() {
+	let dropped = Rc::new(Cell::new(false));
+	let checker = DropChecker(Rc::clone(&dropped)).remove_trait_impls();
+	assert!(!dropped.get());
+	assert_eq!(Rc::strong_count(&checker.0), 2);
+}
+
+#[test]
+fn test_msrv_enum() {
+	let dropped = Rc::new(Cell::new(false));
+	let e = Either::Right { checker: DropChecker(Rc::clone(&dropped)) }.remove_trait_impls();
+	match e {
+		EitherWithoutTraitImpls::Right { checker } => {
+			assert!(!dropped.get());
+			drop(checker);
+			assert!(dropped.get());
+		}
+		EitherWithoutTraitImpls::Left(_) => panic!(),
+	}
+}
+
+#[test]
+fn test_msrv_enum_tuple_variant() {
+	let dropped = Rc::new(Cell::new(false));
+	let e = Either::Left(DropChecker(Rc::clone(&dropped))).remove_trait_impls();
+	if let EitherWithoutTraitImpls::Left(checker) = e {
+		assert_eq!(Rc::strong_count(&checker.0), 2);
+	} else {
+		panic!();
+	}
+	assert!(dropped.get());
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(named)]
+struct Rect {
+	x: u32,
+	y: u32,
+	width: u32,
+	height: u32,
+	#[destructure(skip)]
+	label: String
+}
+
+impl Drop for Rect {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(named, into_parts)]
+struct Pair<'a, T: Clone> where T: Default {
+	first: T,
+	second: &'a str
+}
+
+impl<'a, T: Clone> Drop for Pair<'a, T> where T: Default {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_named() {
+	let rect = Rect { x: 1, y: 2, width: 3, height: 4, label: "rect".to_owned() };
+	let RectParts { x, y, width, height } = rect.destructure_named();
+	assert_eq!((x, y, width, height), (1, 2, 3, 4));
+
+	let rect = Rect { x: 5, y: 6, width: 7, height: 8, label: "rect".to_owned() };
+	assert_eq!(rect.destructure(), (5, 6, 7, 8));
+}
+
+#[test]
+fn test_named_generic() {
+	let parts = Pair { first: 7u8, second: "pair" }.destructure_named();
+	assert_eq!(parts.first, 7);
+	assert_eq!(parts.second, "pair");
+
+	let (first, second) = Pair { first: 8u8, second: "pair" }.into_parts();
+	assert_eq!((first, second), (8, "pair"));
+}
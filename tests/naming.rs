@@ -0,0 +1,108 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(into_parts)]
+struct Foo {
+	x: i64,
+	y: f32
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(into_parts, alias = "destructure")]
+struct Bar(String);
+
+impl Drop for Bar {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_into_parts() {
+	let foo = Foo {
+		x: 7,
+		y: 8.9
+	};
+	let (x,y) = foo.into_parts();
+	assert_eq!(x, 7);
+	assert_eq!(y, 8.9);
+}
+
+#[test]
+fn test_alias() {
+	let (s,) = Bar("foo".to_owned()).into_parts();
+	assert_eq!(s, "foo");
+	let (s,) = Bar("bar".to_owned()).destructure();
+	assert_eq!(s, "bar");
+}
+
+trait Destructure {
+	fn destructure(&self) -> &'static str;
+}
+
+#[derive(destructure)]
+#[destructure(rename = "take_fields", with_shell)]
+struct Baz {
+	a: u8
+}
+
+impl Destructure for Baz {
+	fn destructure(&self) -> &'static str {
+		"from the trait"
+	}
+}
+
+impl Drop for Baz {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_rename() {
+	let baz = Baz { a: 1 };
+	assert_eq!(baz.destructure(), "from the trait");
+	let (a,) = baz.take_fields();
+	assert_eq!(a, 1);
+	let ((a,), _shell) = Baz { a: 2 }.take_fields_with_shell();
+	assert_eq!(a, 2);
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(name = "QuxParts")]
+struct Qux {
+	a: u8
+}
+
+impl Drop for Qux {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(suffix = "Raw")]
+enum Quux {
+	A(u8)
+}
+
+impl Drop for Quux {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_remove_trait_impls_name() {
+	let QuxParts { a } = Qux { a: 3 }.remove_trait_impls();
+	assert_eq!(a, 3);
+	let QuuxRaw::A(a) = Quux::A(4).remove_trait_impls();
+	assert_eq!(a, 4);
+}
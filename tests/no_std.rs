@@ -0,0 +1,43 @@
+#[macro_use]
+extern crate derive_destructure;
+extern crate alloc;
+
+use alloc::borrow::Cow;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(owned, no_std)]
+struct Guard<'a> {
+	name: &'a str,
+	note: Cow<'a, str>
+}
+
+impl<'a> Drop for Guard<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(no_std)]
+struct Counter {
+	count: u32
+}
+
+impl Drop for Counter {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_no_std() {
+	let name = "guard".to_owned();
+	let guard = Guard { name: &name, note: Cow::Borrowed("note") }.remove_trait_impls();
+	drop(name);
+	let note: String = guard.note;
+	assert_eq!(guard.name, "guard");
+	assert_eq!(note, "note");
+
+	let (count,) = Counter { count: 1 }.destructure();
+	assert_eq!(count, 1);
+}
@@ -0,0 +1,89 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::borrow::Cow;
+use std::thread;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(owned)]
+struct Guard<'a, 'b> {
+	name: &'a str,
+	values: &'b [u32],
+	note: Cow<'a, str>,
+	count: usize
+}
+
+impl<'a, 'b> Drop for Guard<'a, 'b> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(owned)]
+struct Wrapper<'a, T: Clone + 'a>(&'a T, u8);
+
+impl<'a, T: Clone + 'a> Drop for Wrapper<'a, T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(owned)]
+enum Event<'a> {
+	Message { text: &'a str },
+	Code(Cow<'a, [u8]>)
+}
+
+impl<'a> Drop for Event<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_owned_struct() {
+	let name = String::from("guard");
+	let values = vec![1, 2, 3];
+	let guard = Guard {
+		name: &name,
+		values: &values,
+		note: Cow::Borrowed("note"),
+		count: 3
+	};
+	let snapshot = guard.remove_trait_impls();
+	drop(name);
+	drop(values);
+	let snapshot = thread::spawn(move || snapshot).join().unwrap();
+	let name: String = snapshot.name;
+	let values: Vec<u32> = snapshot.values;
+	let note: String = snapshot.note;
+	assert_eq!(name, "guard");
+	assert_eq!(values, vec![1, 2, 3]);
+	assert_eq!(note, "note");
+	assert_eq!(snapshot.count, 3);
+}
+
+#[test]
+fn test_owned_generic_tuple_struct() {
+	let value = vec![String::from("a")];
+	let snapshot = Wrapper(&value, 7).remove_trait_impls();
+	drop(value);
+	let owned: Vec<String> = snapshot.0;
+	assert_eq!(owned, vec![String::from("a")]);
+	assert_eq!(snapshot.1, 7);
+}
+
+#[test]
+fn test_owned_enum() {
+	let text = String::from("hello");
+	match (Event::Message { text: &text }).remove_trait_impls() {
+		EventWithoutTraitImpls::Message { text } => assert_eq!(text, "hello"),
+		EventWithoutTraitImpls::Code(_) => panic!("wrong variant"),
+	}
+	match Event::Code(Cow::Owned(vec![1, 2])).remove_trait_impls() {
+		EventWithoutTraitImpls::Code(code) => assert_eq!(code, vec![1, 2]),
+		EventWithoutTraitImpls::Message { .. } => panic!("wrong variant"),
+	}
+}
@@ -0,0 +1,57 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(patch, with_shell)]
+#[repr(C, packed)]
+struct Packet {
+	kind: u8,
+	name: String,
+	#[destructure(skip)]
+	checksum: Vec<u8>,
+	len: u32
+}
+
+impl Drop for Packet {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure, remove_trait_impls)]
+#[repr(C, packed(2))]
+struct Pair(u8, String);
+
+impl Drop for Pair {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn packet() -> Packet {
+	Packet { kind: 1, name: "packet".to_owned(), checksum: vec![1, 2], len: 6 }
+}
+
+#[test]
+fn test_packed() {
+	let (kind, name, len) = packet().destructure();
+	assert_eq!((kind, name.as_str(), len), (1, "packet", 6));
+
+	let ((kind, name, len), _shell) = packet().destructure_with_shell();
+	assert_eq!((kind, name.as_str(), len), (1, "packet", 6));
+
+	let packet = packet().apply(PacketPatch { name: Some("patched".to_owned()), ..Default::default() });
+	let stripped = packet.remove_trait_impls();
+	let name = stripped.name;
+	let checksum = stripped.checksum;
+	assert_eq!((stripped.kind, name.as_str(), &checksum[..], { stripped.len }), (1, "patched", &[1, 2][..], 6));
+}
+
+#[test]
+fn test_packed_tuple() {
+	let (byte, string) = Pair(1, "pair".to_owned()).destructure();
+	assert_eq!((byte, string.as_str()), (1, "pair"));
+	let pair = Pair(2, "pair".to_owned()).remove_trait_impls();
+	let string = pair.1;
+	assert_eq!((pair.0, string.as_str()), (2, "pair"));
+}
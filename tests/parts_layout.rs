@@ -0,0 +1,29 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::alloc::Layout;
+
+#[derive(destructure)]
+#[destructure(parts_layout)]
+struct Foo<T> {
+	a: u8,
+	b: u64,
+	c: T
+}
+
+impl<T> Drop for Foo<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+const FOO_PARTS_LAYOUT: Layout = Foo::<u16>::parts_layout();
+
+#[test]
+fn test_parts_layout() {
+	assert_eq!(FOO_PARTS_LAYOUT, Layout::new::<(u8, u64, u16)>());
+	assert_eq!(Foo::<String>::parts_layout(), Layout::new::<(u8, u64, String)>());
+
+	let (a, b, c) = Foo { a: 1, b: 2, c: 3u16 }.destructure();
+	assert_eq!((a, b, c), (1, 2, 3));
+}
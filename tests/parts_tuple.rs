@@ -0,0 +1,44 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(parts_tuple)]
+struct Foo<T> {
+	some_str: String,
+	some_int: T
+}
+
+impl<T> Drop for Foo<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(parts_tuple)]
+struct Bar(u8, &'static str);
+
+impl Drop for Bar {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_parts_tuple_debug() {
+	let foo = Foo {
+		some_str: "foo".to_owned(),
+		some_int: 4
+	};
+	let parts = FooPartsTuple(foo.destructure());
+	assert_eq!(format!("{:?}", parts), "Foo { some_str: \"foo\", some_int: 4 }");
+	let (some_str, some_int) = parts.0;
+	assert_eq!(some_str, "foo");
+	assert_eq!(some_int, 4);
+}
+
+#[test]
+fn test_parts_tuple_debug_tuple_struct() {
+	let parts = BarPartsTuple(Bar(1, "bar").destructure());
+	assert_eq!(format!("{:?}", parts), "Bar(1, \"bar\")");
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(RemoveTraitImpls)]
+#[remove_trait_impls(variants(NoDrop(keep_derives)))]
+#[derive(Debug, Destructure)]
+#[destructure(named)]
+struct Foo {
+	a: u32,
+	b: String
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn foo() -> Foo {
+	Foo {
+		a: 1,
+		b: "b".to_owned()
+	}
+}
+
+#[test]
+fn test_pascal_case_destructure() {
+	let (a, b) = foo().destructure();
+	assert_eq!((a, b.as_str()), (1, "b"));
+	let FooParts { a, b } = foo().destructure_named();
+	assert_eq!((a, b.as_str()), (1, "b"));
+}
+
+#[test]
+fn test_pascal_case_remove_trait_impls() {
+	let no_drop = foo().into_no_drop();
+	assert_eq!(format!("{:?}", no_drop), "FooNoDrop { a: 1, b: \"b\" }");
+	assert_eq!((no_drop.a, no_drop.b.as_str()), (1, "b"));
+	let FooWithoutTraitImpls { a, b } = foo().remove_trait_impls();
+	assert_eq!((a, b.as_str()), (1, "b"));
+}
@@ -0,0 +1,61 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::rc::Rc;
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(patch)]
+struct Foo {
+	x: i64,
+	y: String
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_patch_named() {
+	let foo = Foo {
+		x: 7,
+		y: "foo".to_owned()
+	};
+	let foo = foo.apply(FooPatch {
+		y: Some("bar".to_owned()),
+		..Default::default()
+	});
+	let (x,y) = foo.destructure();
+	assert_eq!(x, 7);
+	assert_eq!(y, "bar");
+}
+
+#[derive(destructure)]
+#[destructure(patch)]
+struct Bar<T>(T, Rc<Cell<bool>>);
+
+impl<T> Drop for Bar<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+struct DropChecker(Rc<Cell<bool>>);
+
+impl Drop for DropChecker {
+	fn drop(&mut self) {
+		self.0.set(true);
+	}
+}
+
+#[test]
+fn test_patch_drops_replaced_fields() {
+	let dropped = Rc::new(Cell::new(false));
+	let bar = Bar(DropChecker(Rc::clone(&dropped)), Rc::new(Cell::new(false)));
+	let bar = bar.apply(BarPatch(Some(DropChecker(Rc::new(Cell::new(false)))), None));
+	assert!(dropped.get());
+	let (checker, _) = bar.destructure();
+	assert!(!checker.0.get());
+}
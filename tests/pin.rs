@@ -0,0 +1,51 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::pin::Pin;
+use std::rc::Rc;
+
+#[derive(destructure)]
+struct PinnedBoxes {
+	boxed: Pin<Box<i32>>,
+	rc: Pin<Rc<String>>
+}
+
+impl Drop for PinnedBoxes {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+struct PinnedRef<'a> {
+	#[destructure(unsafe_pin_ref)]
+	r: Pin<&'a mut i32>
+}
+
+impl<'a> Drop for PinnedRef<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_pinned_owning_pointers() {
+	let x = PinnedBoxes {
+		boxed: Box::pin(5),
+		rc: Rc::pin("foo".to_owned())
+	};
+	let (boxed, rc) = x.destructure();
+	assert_eq!(*boxed, 5);
+	assert_eq!(*rc, "foo");
+}
+
+#[test]
+fn test_pinned_ref_opt_in() {
+	let mut i = 5;
+	let x = PinnedRef {
+		r: Pin::new(&mut i)
+	};
+	let (mut r,) = x.destructure();
+	*r = 6;
+	assert_eq!(i, 6);
+}
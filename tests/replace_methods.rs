@@ -0,0 +1,41 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+struct Token(u32);
+
+#[derive(destructure)]
+#[destructure(replace_methods)]
+struct Session<'a> {
+	token: Token,
+	name: String,
+	drops: &'a Cell<u32>
+}
+
+impl<'a> Drop for Session<'a> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[test]
+fn test_replace_methods() {
+	let drops = Cell::new(0);
+	{
+		let mut session = Session { token: Token(1), name: "session".to_owned(), drops: &drops };
+		let old = session.replace_token(Token(2));
+		assert_eq!(old.0, 1);
+		assert_eq!(session.replace_name("renamed".to_owned()), "session");
+		let (token, name, _) = session.destructure();
+		assert_eq!((token.0, name.as_str()), (2, "renamed"));
+	}
+	assert_eq!(drops.get(), 0);
+	let other = Cell::new(0);
+	{
+		let mut session = Session { token: Token(1), name: "session".to_owned(), drops: &drops };
+		assert!(std::ptr::eq(session.replace_drops(&other), &drops));
+	}
+	assert_eq!(drops.get(), 0);
+	assert_eq!(other.get(), 1);
+}
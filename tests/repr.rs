@@ -0,0 +1,55 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::mem::{align_of, size_of};
+
+#[derive(remove_trait_impls)]
+#[repr(C)]
+struct Header {
+	kind: u8,
+	len: u32,
+	flags: u8
+}
+
+impl Drop for Header {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[repr(C, align(16))]
+struct Aligned(u8);
+
+impl Drop for Aligned {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[repr(u8)]
+enum Kind {
+	A,
+	B
+}
+
+impl Drop for Kind {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_repr() {
+	assert_eq!(size_of::<HeaderWithoutTraitImpls>(), 12);
+	let header = Header { kind: 1, len: 2, flags: 3 }.remove_trait_impls();
+	assert_eq!((header.kind, header.len, header.flags), (1, 2, 3));
+
+	assert_eq!(align_of::<AlignedWithoutTraitImpls>(), 16);
+	assert_eq!(Aligned(4).remove_trait_impls().0, 4);
+
+	assert_eq!(size_of::<KindWithoutTraitImpls>(), 1);
+	assert_eq!(Kind::A.remove_trait_impls() as u8, 0);
+	assert_eq!(Kind::B.remove_trait_impls() as u8, 1);
+}
@@ -0,0 +1,48 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::mem::ManuallyDrop;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(repr_c_tagged)]
+#[repr(u8)]
+enum Message {
+	Quit = 1,
+	Move { x: i32, y: i32 } = 5,
+	Write(String)
+}
+
+impl Drop for Message {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn take_text(message: MessageWithoutTraitImpls) -> Option<String> {
+	match message.tag {
+		MessageWithoutTraitImplsTag::Write => {
+			let payload = unsafe { ManuallyDrop::into_inner(message.payload.Write) };
+			Some(payload.0)
+		}
+		MessageWithoutTraitImplsTag::Move => {
+			let payload = unsafe { &message.payload.Move };
+			assert_eq!((payload.x, payload.y), (3, -4));
+			None
+		}
+		MessageWithoutTraitImplsTag::Quit => {
+			let MessageWithoutTraitImplsQuit = unsafe { ManuallyDrop::into_inner(message.payload.Quit) };
+			None
+		}
+	}
+}
+
+#[test]
+fn test_repr_c_tagged() {
+	assert_eq!(take_text(Message::Write("hi".to_owned()).remove_trait_impls()), Some("hi".to_owned()));
+	assert_eq!(take_text(Message::Move { x: 3, y: -4 }.remove_trait_impls()), None);
+	let quit = Message::Quit.remove_trait_impls();
+	assert_eq!(quit.tag, MessageWithoutTraitImplsTag::Quit);
+	assert_eq!(quit.tag as u8, 1);
+	assert_eq!(MessageWithoutTraitImplsTag::Move as u8, 5);
+	assert_eq!(take_text(quit), None);
+}
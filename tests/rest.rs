@@ -0,0 +1,124 @@
+#[macro_use]
+extern crate derive_destructure;
+extern crate derive_destructure_traits;
+
+use std::cell::Cell;
+
+struct Resource<'a> {
+	drops: &'a Cell<u32>
+}
+
+impl<'a> Drop for Resource<'a> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(rest)]
+struct Session<'a, T> {
+	name: String,
+	payload: T,
+	resource: Resource<'a>,
+	#[destructure(skip)]
+	log: Resource<'a>
+}
+
+impl<'a, T> Drop for Session<'a, T> {
+	fn drop(&mut self) {
+		panic!("Session shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(impl_trait)]
+struct Address {
+	host: String,
+	port: u16
+}
+
+impl Drop for Address {
+	fn drop(&mut self) {
+		panic!("Address shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(rest)]
+struct Server {
+	#[destructure(flatten)]
+	address: Address,
+	name: String
+}
+
+impl Drop for Server {
+	fn drop(&mut self) {
+		panic!("Server shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_take_field() {
+	let drops = Cell::new(0);
+	let session = Session {
+		name: "session".to_owned(),
+		payload: 5u8,
+		resource: Resource { drops: &drops },
+		log: Resource { drops: &drops },
+	};
+	let (name, rest) = session.take_name();
+	assert_eq!(name, "session");
+	assert_eq!(drops.get(), 1);
+	let SessionWithoutName { payload, resource } = rest;
+	assert_eq!(payload, 5);
+	assert_eq!(drops.get(), 1);
+	drop(resource);
+	assert_eq!(drops.get(), 2);
+}
+
+#[test]
+fn test_rest_drops_remaining_fields() {
+	let drops = Cell::new(0);
+	let session = Session {
+		name: "session".to_owned(),
+		payload: vec![1],
+		resource: Resource { drops: &drops },
+		log: Resource { drops: &drops },
+	};
+	let (payload, rest) = session.take_payload();
+	assert_eq!(payload, vec![1]);
+	assert_eq!(rest.name, "session");
+	drop(rest);
+	assert_eq!(drops.get(), 2);
+
+	let session = Session {
+		name: "session".to_owned(),
+		payload: (),
+		resource: Resource { drops: &drops },
+		log: Resource { drops: &drops },
+	};
+	let (resource, rest) = session.take_resource();
+	drop::<SessionWithoutResource<()>>(rest);
+	assert_eq!(drops.get(), 3);
+	drop(resource);
+	assert_eq!(drops.get(), 4);
+}
+
+#[test]
+fn test_take_flattened_field() {
+	let server = Server {
+		address: Address { host: "localhost".to_owned(), port: 80 },
+		name: "web".to_owned(),
+	};
+	let ((host, port), rest) = server.take_address();
+	assert_eq!((host.as_str(), port), ("localhost", 80));
+	assert_eq!(rest.name, "web");
+
+	let server = Server {
+		address: Address { host: "localhost".to_owned(), port: 443 },
+		name: "web".to_owned(),
+	};
+	let (name, ServerWithoutName { address }) = server.take_name();
+	assert_eq!(name, "web");
+	assert_eq!(address, ("localhost".to_owned(), 443));
+}
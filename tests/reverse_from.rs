@@ -0,0 +1,56 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(reverse_from)]
+struct Counter<T> {
+	name: String,
+	value: T
+}
+
+impl<T> Drop for Counter<T> {
+	fn drop(&mut self) {
+		DROPS.fetch_add(1, Ordering::SeqCst);
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(reverse_from)]
+enum Message {
+	Text(String),
+	Move { x: i32, y: i32 },
+	Quit
+}
+
+impl Drop for Message {
+	fn drop(&mut self) {}
+}
+
+#[test]
+fn test_reverse_from() {
+	let mut stripped = Counter { name: "counter".to_owned(), value: 1u8 }.remove_trait_impls();
+	stripped.value += 1;
+	assert_eq!(DROPS.load(Ordering::SeqCst), 0);
+
+	let counter = Counter::from(stripped);
+	assert_eq!(counter.name, "counter");
+	assert_eq!(counter.value, 2);
+	drop(counter);
+	assert_eq!(DROPS.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_reverse_from_enum() {
+	let message: Message = Message::Text("hello".to_owned()).remove_trait_impls().into();
+	assert!(matches!(message, Message::Text(ref text) if text == "hello"));
+
+	let message = Message::from(MessageWithoutTraitImpls::Move { x: 1, y: 2 });
+	assert!(matches!(message, Message::Move { x: 1, y: 2 }));
+
+	let message = Message::from(Message::Quit.remove_trait_impls());
+	assert!(matches!(message, Message::Quit));
+}
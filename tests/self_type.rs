@@ -0,0 +1,80 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure, remove_trait_impls)]
+#[destructure(named, patch)]
+struct Node<T> {
+	value: T,
+	next: Option<Box<Self>>
+}
+
+impl<T> Drop for Node<T> {
+	fn drop(&mut self) {}
+}
+
+#[derive(destructure, remove_trait_impls)]
+enum List {
+	Cons(u32, Box<Self>),
+	Nil
+}
+
+impl Drop for List {
+	fn drop(&mut self) {}
+}
+
+#[derive(remove_trait_impls)]
+struct Buffer {
+	bytes: [u8; Self::LEN],
+	len: [usize; <Self>::LEN - 3]
+}
+
+impl Buffer {
+	const LEN: usize = 4;
+}
+
+impl Drop for Buffer {
+	fn drop(&mut self) {}
+}
+
+#[test]
+fn test_self_struct() {
+	let node = Node { value: 1, next: Some(Box::new(Node { value: 2, next: None })) };
+	let stripped: NodeWithoutTraitImpls<i32> = node.remove_trait_impls();
+	let next: Box<Node<i32>> = stripped.next.unwrap();
+	assert_eq!((stripped.value, next.value), (1, 2));
+
+	let NodeParts { value, next } = Node { value: 3, next: None }.destructure_named();
+	let next: Option<Box<Node<i32>>> = next;
+	assert_eq!(value, 3);
+	assert!(next.is_none());
+
+	let node = Node { value: 4, next: None }.apply(NodePatch { next: Some(Some(Box::new(Node { value: 5, next: None }))), ..Default::default() });
+	assert_eq!(node.next.as_ref().map(|next| next.value), Some(5));
+}
+
+#[test]
+fn test_self_enum() {
+	match List::Cons(1, Box::new(List::Nil)).remove_trait_impls() {
+		ListWithoutTraitImpls::Cons(head, tail) => {
+			let tail: Box<List> = tail;
+			assert_eq!(head, 1);
+			assert!(matches!(*tail, List::Nil));
+		}
+		ListWithoutTraitImpls::Nil => panic!("wrong variant"),
+	}
+	match List::Cons(2, Box::new(List::Nil)).destructure() {
+		ListDestructured::Cons(head, tail) => {
+			let tail: Box<List> = tail;
+			assert_eq!(head, 2);
+			assert!(matches!(*tail, List::Nil));
+		}
+		ListDestructured::Nil => panic!("wrong variant"),
+	}
+}
+
+#[test]
+fn test_self_path() {
+	let stripped = Buffer { bytes: [1, 2, 3, 4], len: [4] }.remove_trait_impls();
+	assert_eq!(stripped.bytes, [1, 2, 3, 4]);
+	assert_eq!(stripped.len, [4]);
+}
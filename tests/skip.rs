@@ -0,0 +1,82 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+struct Logger<'a> {
+	drops: &'a Cell<u32>
+}
+
+impl<'a> Drop for Logger<'a> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[derive(Clone, Copy)]
+struct Token(u8);
+
+#[derive(destructure)]
+#[destructure(fields_type, with_shell)]
+struct Session<'a> {
+	id: u32,
+	#[destructure(skip)]
+	logger: Logger<'a>,
+	name: String,
+	#[destructure(skip)]
+	token: Token,
+	#[destructure(skip)]
+	count: usize
+}
+
+impl<'a> Drop for Session<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+struct OnlySkipped<'a>(#[destructure(skip)] Logger<'a>);
+
+impl<'a> Drop for OnlySkipped<'a> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn session(drops: &Cell<u32>) -> Session<'_> {
+	Session {
+		id: 1,
+		logger: Logger { drops },
+		name: "session".to_owned(),
+		token: Token(2),
+		count: 3
+	}
+}
+
+#[test]
+fn test_skip() {
+	let drops = Cell::new(0);
+	let s = session(&drops);
+	assert_eq!(s.token.0 as usize + s.count, 5);
+	let (id, name): SessionFields<'_> = s.destructure();
+	assert_eq!(id, 1);
+	assert_eq!(name, "session");
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_skip_with_shell() {
+	let drops = Cell::new(0);
+	let ((id, name), _shell) = session(&drops).destructure_with_shell();
+	assert_eq!(id, 1);
+	assert_eq!(name, "session");
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_only_skipped() {
+	let drops = Cell::new(0);
+	OnlySkipped(Logger { drops: &drops }).destructure();
+	assert_eq!(drops.get(), 1);
+}
@@ -0,0 +1,42 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(take_parts)]
+struct Machine<'a> {
+	buffer: Vec<u8>,
+	state: Option<String>,
+	#[destructure(forget)]
+	drops: &'a Cell<u32>
+}
+
+impl<'a> Drop for Machine<'a> {
+	fn drop(&mut self) {
+		assert!(self.buffer.is_empty());
+		assert_eq!(self.state, None);
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+#[derive(destructure)]
+#[destructure(take_parts, allow_no_drop)]
+struct Unit;
+
+#[test]
+fn test_take_parts() {
+	let drops = Cell::new(0);
+	{
+		let mut machine = Machine { buffer: vec![1, 2], state: Some("running".to_owned()), drops: &drops };
+		assert_eq!(machine.take_parts(), (vec![1, 2], Some("running".to_owned())));
+		assert_eq!(machine.take_parts(), (Vec::new(), None));
+	}
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_take_parts_unit() {
+	Unit.take_parts();
+	Unit.destructure();
+}
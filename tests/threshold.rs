@@ -0,0 +1,60 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(threshold = 3, alias = "into_parts")]
+struct Config {
+	name: String,
+	port: u16,
+	#[destructure(skip)]
+	_cache: Vec<u8>,
+	verbose: bool,
+	retries: u32
+}
+
+impl Drop for Config {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(threshold = 3)]
+struct Small<T> {
+	a: T,
+	#[destructure(skip)]
+	_b: T,
+	#[destructure(skip)]
+	_c: T,
+	d: T
+}
+
+impl<T> Drop for Small<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn config() -> Config {
+	Config {
+		name: "server".to_owned(),
+		port: 8080,
+		_cache: Vec::new(),
+		verbose: true,
+		retries: 3
+	}
+}
+
+#[test]
+fn test_threshold_exceeded() {
+	let ConfigParts { name, port, verbose, retries } = config().destructure();
+	assert_eq!((name.as_str(), port, verbose, retries), ("server", 8080, true, 3));
+	let parts = config().into_parts();
+	assert_eq!(parts.port, 8080);
+}
+
+#[test]
+fn test_threshold_not_exceeded() {
+	let (a, d) = Small { a: 1, _b: 2, _c: 3, d: 4 }.destructure();
+	assert_eq!((a, d), (1, 4));
+}
@@ -0,0 +1,65 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(try_restore(validate = "check_range", error = "String"))]
+struct Range {
+	start: u32,
+	end: u32
+}
+
+impl Drop for Range {
+	fn drop(&mut self) {}
+}
+
+fn check_range(range: &RangeWithoutTraitImpls) -> Result<(), String> {
+	if range.start <= range.end {
+		Ok(())
+	} else {
+		Err(format!("{} > {}", range.start, range.end))
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(try_restore(validate = "Shape::check", error = "()"))]
+enum Shape {
+	Circle(f64),
+	Rect { w: f64, h: f64 }
+}
+
+impl Drop for Shape {
+	fn drop(&mut self) {}
+}
+
+impl Shape {
+	fn check(shape: &ShapeWithoutTraitImpls) -> Result<(), ()> {
+		match *shape {
+			ShapeWithoutTraitImpls::Circle(r) if r >= 0.0 => Ok(()),
+			ShapeWithoutTraitImpls::Rect { w, h } if w >= 0.0 && h >= 0.0 => Ok(()),
+			_ => Err(()),
+		}
+	}
+}
+
+#[test]
+fn test_try_restore_struct() {
+	let mut range = Range { start: 1, end: 5 }.remove_trait_impls();
+	range.start = 7;
+	let (range, err) = range.try_restore().err().unwrap();
+	assert_eq!(err, "7 > 5");
+	let mut range = range;
+	range.end = 10;
+	let range = range.try_restore().ok().unwrap();
+	assert_eq!((range.start, range.end), (7, 10));
+}
+
+#[test]
+fn test_try_restore_enum() {
+	let circle = Shape::Circle(1.0).remove_trait_impls();
+	match circle.try_restore() {
+		Ok(Shape::Circle(r)) => assert_eq!(r, 1.0),
+		_ => panic!(),
+	}
+	let rect = ShapeWithoutTraitImpls::Rect { w: -1.0, h: 2.0 };
+	assert!(rect.try_restore().is_err());
+}
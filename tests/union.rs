@@ -0,0 +1,52 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::mem::ManuallyDrop;
+
+#[derive(remove_trait_impls)]
+#[repr(C)]
+union Value {
+	int: u64,
+	float: f64,
+	text: ManuallyDrop<String>
+}
+
+impl Drop for Value {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(assert_layout)]
+#[repr(C)]
+union Bits<T: Copy> {
+	value: T,
+	raw: [u8; 4]
+}
+
+impl<T: Copy> Drop for Bits<T> {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_union() {
+	let value = Value { int: 42 }.remove_trait_impls();
+	assert_eq!(unsafe { value.int }, 42);
+
+	let value = Value { float: 1.5 }.remove_trait_impls();
+	assert_eq!(unsafe { value.float }, 1.5);
+
+	let mut value = Value { text: ManuallyDrop::new("hello".to_owned()) }.remove_trait_impls();
+	let text = unsafe { ManuallyDrop::take(&mut value.text) };
+	assert_eq!(text, "hello");
+}
+
+#[test]
+fn test_generic_union() {
+	let bits = Bits { value: 0x01020304u32 }.remove_trait_impls();
+	assert_eq!(unsafe { bits.raw }, 0x01020304u32.to_ne_bytes());
+	assert_eq!(unsafe { bits.value }, 0x01020304);
+}
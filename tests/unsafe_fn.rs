@@ -0,0 +1,71 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(unsafe_fn, alias = "into_fields", named, with_shell)]
+struct LockGuard {
+	name: String,
+	depth: u32
+}
+
+impl Drop for LockGuard {
+	fn drop(&mut self) {
+		panic!("LockGuard shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(unsafe_fn, ext_trait)]
+struct Token(u64);
+
+impl Drop for Token {
+	fn drop(&mut self) {
+		panic!("Token shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(unsafe_fn, alias = "into_fields", try_into_variants)]
+enum Lock {
+	Held(String),
+	Free
+}
+
+impl Drop for Lock {
+	fn drop(&mut self) {
+		panic!("Lock shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_unsafe_fn() {
+	let guard = LockGuard { name: "guard".to_owned(), depth: 1 };
+	assert_eq!(unsafe { guard.destructure() }, ("guard".to_owned(), 1));
+	let guard = LockGuard { name: "alias".to_owned(), depth: 2 };
+	assert_eq!(unsafe { guard.into_fields() }, ("alias".to_owned(), 2));
+	let guard = LockGuard { name: "named".to_owned(), depth: 3 };
+	let LockGuardParts { name, depth } = unsafe { guard.destructure_named() };
+	assert_eq!((name, depth), ("named".to_owned(), 3));
+	let guard = LockGuard { name: "shell".to_owned(), depth: 4 };
+	let (parts, _shell) = unsafe { guard.destructure_with_shell() };
+	assert_eq!(parts, ("shell".to_owned(), 4));
+}
+
+#[test]
+fn test_unsafe_fn_ext_trait() {
+	assert_eq!(unsafe { Token(5).destructure() }, (5,));
+}
+
+#[test]
+fn test_unsafe_fn_enum() {
+	match unsafe { Lock::Held("held".to_owned()).destructure() } {
+		LockDestructured::Held(name) => assert_eq!(name, "held"),
+		LockDestructured::Free => panic!("expected Held"),
+	}
+	match unsafe { Lock::Free.into_fields() } {
+		LockDestructured::Free => {}
+		LockDestructured::Held(_) => panic!("expected Free"),
+	}
+	let lock = unsafe { Lock::Free.try_into_held() }.unwrap_err();
+	assert_eq!(unsafe { lock.try_into_free() }.ok(), Some(()));
+}
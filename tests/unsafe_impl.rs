@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::thread;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(unsafe_impl(Send, Sync))]
+struct RawBuf {
+	ptr: *mut u8,
+	len: usize
+}
+
+unsafe impl Send for RawBuf {}
+unsafe impl Sync for RawBuf {}
+
+impl Drop for RawBuf {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_unsafe_impl_send_sync() {
+	let mut data = [1u8, 2, 3];
+	let buf = RawBuf {
+		ptr: data.as_mut_ptr(),
+		len: data.len()
+	};
+	let buf = buf.remove_trait_impls();
+	assert_send_sync(&buf);
+	let len = thread::spawn(move || {
+		let buf = buf;
+		assert!(!buf.ptr.is_null());
+		buf.len
+	}).join().unwrap();
+	assert_eq!(len, 3);
+}
@@ -0,0 +1,35 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::marker::PhantomData;
+
+#[derive(destructure)]
+#[destructure(named, rest, fields_type, parts_tuple)]
+struct Handle<'a, T, U: Clone + Into<T>> where T: Default {
+	#[destructure(skip)]
+	_marker: PhantomData<&'a T>,
+	#[destructure(forget)]
+	_extra: U,
+	id: u32
+}
+
+impl<'a, T, U: Clone + Into<T>> Drop for Handle<'a, T, U> where T: Default {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn handle<'a>() -> Handle<'a, String, &'static str> {
+	Handle { _marker: PhantomData, _extra: "extra", id: 7 }
+}
+
+#[test]
+fn test_unused_generics() {
+	let fields: HandleFields = handle().destructure();
+	assert_eq!(fields, (7,));
+	let parts: HandleParts = handle().destructure_named();
+	assert_eq!(parts.id, 7);
+	let (id, HandleWithoutId {}) = handle().take_id();
+	assert_eq!(id, 7);
+	assert_eq!(format!("{:?}", HandlePartsTuple(handle().destructure())), "Handle { id: 7 }");
+}
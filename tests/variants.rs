@@ -0,0 +1,82 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(variants(NoDrop(keep_derives), Bare(), Printable(derive(Debug))))]
+#[derive(Clone, PartialEq, Debug)]
+struct Foo {
+	a: u32,
+	b: String
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(remove_trait_impls)]
+#[remove_trait_impls(variants(Copyable(derive(Clone, Copy))))]
+enum Bar {
+	A(u8),
+	B { x: i32 }
+}
+
+impl Drop for Bar {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+fn foo() -> Foo {
+	Foo {
+		a: 1,
+		b: "b".to_owned()
+	}
+}
+
+#[test]
+fn test_keep_derives() {
+	let no_drop = foo().into_no_drop();
+	let copy = no_drop.clone();
+	assert_eq!(no_drop, copy);
+	assert_eq!(format!("{:?}", copy), "FooNoDrop { a: 1, b: \"b\" }");
+	assert_eq!(copy.a, 1);
+	assert_eq!(copy.b, "b");
+}
+
+#[test]
+fn test_bare() {
+	let FooBare { a, b } = foo().into_bare();
+	assert_eq!(a, 1);
+	assert_eq!(b, "b");
+}
+
+#[test]
+fn test_derive() {
+	let printable = foo().into_printable();
+	assert_eq!(format!("{:?}", printable), "FooPrintable { a: 1, b: \"b\" }");
+	assert_eq!(printable.a + 1, 2);
+	assert_eq!(printable.b, "b");
+	let main = foo().remove_trait_impls();
+	assert_eq!(main.a, 1);
+	assert_eq!(main.b, "b");
+}
+
+#[test]
+fn test_enum_variants() {
+	let a = Bar::A(3).into_copyable();
+	let copy = a;
+	match (a, copy) {
+		(BarCopyable::A(x), BarCopyable::A(y)) => assert_eq!(x + y, 6),
+		_ => panic!("wrong variant"),
+	}
+	match (Bar::B { x: -1 }).remove_trait_impls() {
+		BarWithoutTraitImpls::B { x } => assert_eq!(x, -1),
+		BarWithoutTraitImpls::A(x) => panic!("wrong variant: {}", x),
+	}
+	match (Bar::B { x: 2 }).into_copyable() {
+		BarCopyable::B { x } => assert_eq!(x, 2),
+		BarCopyable::A(x) => panic!("wrong variant: {}", x),
+	}
+}
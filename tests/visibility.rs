@@ -0,0 +1,70 @@
+#[macro_use]
+extern crate derive_destructure;
+
+mod shapes {
+	#[derive(remove_trait_impls)]
+	pub struct Circle {
+		pub radius: f64,
+		id: u32
+	}
+
+	impl Drop for Circle {
+		fn drop(&mut self) {
+			panic!("We don't want to drop this");
+		}
+	}
+
+	#[derive(remove_trait_impls)]
+	pub struct Point(pub i32, pub(crate) i32);
+
+	impl Drop for Point {
+		fn drop(&mut self) {
+			panic!("We don't want to drop this");
+		}
+	}
+
+	#[derive(remove_trait_impls)]
+	pub enum Shape {
+		Circle(f64),
+		Square { side: f64 }
+	}
+
+	impl Drop for Shape {
+		fn drop(&mut self) {
+			panic!("We don't want to drop this");
+		}
+	}
+
+	pub fn circle(radius: f64) -> CircleWithoutTraitImpls {
+		Circle { radius, id: 1 }.remove_trait_impls()
+	}
+
+	pub fn circle_id(circle: &CircleWithoutTraitImpls) -> u32 {
+		circle.id
+	}
+
+	pub fn point(x: i32, y: i32) -> PointWithoutTraitImpls {
+		Point(x, y).remove_trait_impls()
+	}
+
+	pub fn shapes() -> (ShapeWithoutTraitImpls, ShapeWithoutTraitImpls) {
+		(Shape::Circle(1.0).remove_trait_impls(), Shape::Square { side: 2.0 }.remove_trait_impls())
+	}
+}
+
+#[test]
+fn test_visibility() {
+	let circle: shapes::CircleWithoutTraitImpls = shapes::circle(2.0);
+	assert_eq!(circle.radius, 2.0);
+	assert_eq!(shapes::circle_id(&circle), 1);
+
+	let point = shapes::point(3, 4);
+	assert_eq!((point.0, point.1), (3, 4));
+
+	match shapes::shapes() {
+		(shapes::ShapeWithoutTraitImpls::Circle(radius), shapes::ShapeWithoutTraitImpls::Square { side }) => {
+			assert_eq!((radius, side), (1.0, 2.0));
+		}
+		_ => panic!("wrong variants"),
+	}
+}
@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(with_fields)]
+struct Request<T> {
+	path: String,
+	body: T,
+	#[destructure(forget)]
+	_handle: Vec<u8>
+}
+
+impl<T> Drop for Request<T> {
+	fn drop(&mut self) {
+		panic!("Request shouldn't be dropped");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(with_fields, ext_trait)]
+struct Unit;
+
+impl Drop for Unit {
+	fn drop(&mut self) {
+		panic!("Unit shouldn't be dropped");
+	}
+}
+
+#[test]
+fn test_with_fields() {
+	let request = Request { path: "/index".to_owned(), body: 3u8, _handle: Vec::new() };
+	let described = request.with_fields(|path, body| format!("{} {}", path, body));
+	assert_eq!(described, "/index 3");
+}
+
+#[test]
+fn test_with_fields_unit() {
+	assert_eq!(Unit.with_fields(|| 5), 5);
+}
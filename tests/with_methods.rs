@@ -0,0 +1,59 @@
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::Cell;
+
+#[derive(destructure)]
+#[destructure(with_methods)]
+struct Guard<'a, T> {
+	name: String,
+	value: T,
+	drops: &'a Cell<u32>
+}
+
+impl<'a, T> Drop for Guard<'a, T> {
+	fn drop(&mut self) {
+		self.drops.set(self.drops.get() + 1);
+	}
+}
+
+// A field named `this` mustn't clash with the names used by the generated methods.
+#[derive(destructure)]
+#[destructure(with_methods)]
+struct Thing {
+	this: u8,
+	other: u8
+}
+
+impl Drop for Thing {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_with_methods() {
+	let drops = Cell::new(0);
+	let other_drops = Cell::new(0);
+	{
+		let guard = Guard { name: "guard".to_owned(), value: 1, drops: &drops };
+		let guard = guard.with_value(2).with_name("renamed".to_owned());
+		assert_eq!(drops.get(), 0);
+		assert_eq!((guard.name.as_str(), guard.value), ("renamed", 2));
+		let guard = guard.with_drops(&other_drops);
+		assert_eq!(guard.destructure(), ("renamed".to_owned(), 2, &other_drops));
+	}
+	assert_eq!(drops.get(), 0);
+	assert_eq!(other_drops.get(), 0);
+	{
+		let guard = Guard { name: "guard".to_owned(), value: vec![1], drops: &drops };
+		let _guard = guard.with_value(vec![2]).with_value(vec![3]);
+	}
+	assert_eq!(drops.get(), 1);
+}
+
+#[test]
+fn test_with_methods_field_named_this() {
+	let thing = Thing { this: 1, other: 2 }.with_this(3).with_other(4);
+	assert_eq!(thing.destructure(), (3, 4));
+}
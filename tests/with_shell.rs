@@ -0,0 +1,50 @@
+#[macro_use]
+extern crate derive_destructure;
+
+#[derive(destructure)]
+#[destructure(with_shell)]
+struct Foo {
+	x: String,
+	y: i32
+}
+
+impl Drop for Foo {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(with_shell)]
+struct Empty;
+
+impl Drop for Empty {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_with_shell() {
+	let foo = Foo {
+		x: "foo".to_owned(),
+		y: 4
+	};
+	let ((x, y), mut shell) = foo.destructure_with_shell();
+	assert_eq!(x, "foo");
+	assert_eq!(y, 4);
+	let foo = shell.write(Foo {
+		x: "bar".to_owned(),
+		y: 5
+	});
+	assert_eq!(foo.x, "bar");
+	let (x, y) = unsafe { shell.assume_init() }.destructure();
+	assert_eq!(x, "bar");
+	assert_eq!(y, 5);
+}
+
+#[test]
+fn test_with_shell_empty() {
+	let ((), _shell) = Empty.destructure_with_shell();
+	Empty.destructure();
+}
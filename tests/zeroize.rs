@@ -0,0 +1,108 @@
+// The shell is wiped with `::zeroize::Zeroize`, so this crate stands in for `zeroize`.
+extern crate self as zeroize;
+#[macro_use]
+extern crate derive_destructure;
+
+use std::cell::RefCell;
+use std::mem::{self, MaybeUninit};
+use std::{ptr, slice};
+
+thread_local! {
+	static WIPED: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+pub trait Zeroize {
+	fn zeroize(&mut self);
+}
+
+impl Zeroize for [MaybeUninit<u8>] {
+	fn zeroize(&mut self) {
+		for byte in self.iter_mut() {
+			unsafe { ptr::write_volatile(byte, MaybeUninit::new(0)) };
+		}
+		WIPED.with(|wiped| wiped.borrow_mut().push(self.len()));
+	}
+}
+
+fn wiped() -> Vec<usize> {
+	WIPED.with(|wiped| wiped.borrow_mut().split_off(0))
+}
+
+#[derive(destructure)]
+#[destructure(zeroize, boxed)]
+struct Key {
+	id: u32,
+	secret: Vec<u8>,
+	#[destructure(skip)]
+	_log: String
+}
+
+impl Drop for Key {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(zeroize)]
+struct Forgotten {
+	#[destructure(forget)]
+	_bytes: [u8; 32]
+}
+
+impl Drop for Forgotten {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[derive(destructure)]
+#[destructure(zeroize, with_shell)]
+struct Secret {
+	id: u64,
+	key: [u8; 24],
+	#[destructure(skip)]
+	_label: String
+}
+
+impl Drop for Secret {
+	fn drop(&mut self) {
+		panic!("We don't want to drop this");
+	}
+}
+
+#[test]
+fn test_zeroize() {
+	let key = Key { id: 7, secret: vec![1, 2, 3], _log: "log".to_owned() };
+	let (id, secret) = key.destructure();
+	assert_eq!(id, 7);
+	assert_eq!(secret, vec![1, 2, 3]);
+	// Only the `ManuallyDrop<Key>` that the fields were read out of is wiped.
+	assert_eq!(wiped(), vec![mem::size_of::<Key>()]);
+}
+
+#[test]
+fn test_zeroize_boxed() {
+	let key = Box::new(Key { id: 8, secret: vec![4, 5], _log: "log".to_owned() });
+	let (id, secret) = key.destructure_boxed();
+	assert_eq!(id, 8);
+	assert_eq!(secret, vec![4, 5]);
+	assert_eq!(wiped(), vec![mem::size_of::<Key>()]);
+}
+
+#[test]
+fn test_zeroize_forgotten() {
+	let () = Forgotten { _bytes: [0xAA; 32] }.destructure();
+	assert_eq!(wiped(), vec![mem::size_of::<Forgotten>()]);
+}
+
+#[test]
+fn test_zeroize_shell() {
+	let secret = Secret { id: u64::MAX, key: [0xAA; 24], _label: "label".to_owned() };
+	let ((id, key), shell) = secret.destructure_with_shell();
+	assert_eq!(id, u64::MAX);
+	assert_eq!(key, [0xAA; 24]);
+	assert_eq!(wiped(), vec![mem::size_of::<Secret>()]);
+	let bytes = unsafe { slice::from_raw_parts(shell.as_ptr() as *const u8, mem::size_of::<Secret>()) };
+	assert!(bytes.iter().all(|&byte| byte == 0));
+}